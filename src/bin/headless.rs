@@ -0,0 +1,283 @@
+//! Steps a rope without opening a window and prints segment state as CSV. Needs no
+//! SDL2 to build or run: `cargo run --bin headless -- --ticks 600`.
+//!
+//! Usage: headless [--ticks N] [--segments N] [--path still|line|circle|zigzag] [--every N]
+//!                 [--dt SECONDS] [--preset default|chain|rubber_band|thread]
+//!                 [--integrator euler|verlet|rk4] [--solver springs|xpbd]
+//!                 [--gravity] [--anchor-head] [--floor Y] [--self-collision]
+//!                 [--load PATH] [--save PATH]
+//!
+//! The total rope energy and the world checksum at the end of the run are printed to
+//! stderr.
+//!
+//! `--load` continues from a world snapshot instead of building a rope, driving the first
+//! rope in it; the rope options are ignored. `--save` writes the world at the end of the
+//! run. Snapshots are JSON when the path ends in `.json` and binary otherwise.
+
+use rope_sim::{
+    integrator, Collider, Environment, ForceField, Integrator, Rope, RopeParams, Shape,
+    SnapshotError, Solver, Vec2, World, NOMINAL_DT,
+};
+use std::env;
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::process;
+
+const USAGE: &str = "usage: headless [--ticks N] [--segments N] [--path NAME] [--every N] \
+                     [--dt SECONDS] [--preset NAME] [--integrator NAME] [--solver NAME] [--gravity] [--anchor-head] [--floor Y] [--self-collision] [--load PATH] [--save PATH]";
+
+const ORIGIN: Vec2 = Vec2 { x: 400.0, y: 300.0 };
+
+#[derive(Debug, Copy, Clone)]
+enum CursorPath {
+    Still,
+    Line,
+    Circle,
+    Zigzag,
+}
+
+impl CursorPath {
+    fn parse(name: &str) -> Option<CursorPath> {
+        match name {
+            "still" => Some(CursorPath::Still),
+            "line" => Some(CursorPath::Line),
+            "circle" => Some(CursorPath::Circle),
+            "zigzag" => Some(CursorPath::Zigzag),
+            _ => None,
+        }
+    }
+
+    fn cursor_at(self, time: f64) -> Vec2 {
+        let t = time / NOMINAL_DT;
+        match self {
+            CursorPath::Still => ORIGIN,
+            CursorPath::Line => ORIGIN + Vec2::new(t * 0.2, 0.0),
+            CursorPath::Circle => {
+                let angle = t / 900.0 * 2.0 * PI;
+                ORIGIN + Vec2::new(angle.cos(), angle.sin()) * 150.0
+            }
+            CursorPath::Zigzag => {
+                let phase = (t / 300.0) % 2.0;
+                let offset = if phase < 1.0 { phase } else { 2.0 - phase };
+                ORIGIN + Vec2::new(t * 0.1, (offset - 0.5) * 200.0)
+            }
+        }
+    }
+}
+
+struct Options {
+    ticks: usize,
+    segments: usize,
+    path: CursorPath,
+    every: usize,
+    dt: f64,
+    params: RopeParams,
+    integrator: Box<dyn Integrator>,
+    solver: Solver,
+    gravity: bool,
+    anchor_head: bool,
+    floor: Option<f64>,
+    self_collision: bool,
+    load: Option<String>,
+    save: Option<String>,
+}
+
+impl Options {
+    fn from_args() -> Result<Options, String> {
+        let mut options = Options {
+            ticks: 900,
+            segments: 40,
+            path: CursorPath::Circle,
+            every: 0,
+            dt: NOMINAL_DT,
+            params: RopeParams::default(),
+            integrator: Box::new(integrator::SemiImplicitEuler),
+            solver: Solver::Springs,
+            gravity: false,
+            anchor_head: false,
+            floor: None,
+            self_collision: false,
+            load: None,
+            save: None,
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+            match arg.as_str() {
+                "--ticks" => options.ticks = parse_number(&value()?)?,
+                "--segments" => options.segments = parse_number(&value()?)?,
+                "--every" => options.every = parse_number(&value()?)?,
+                "--path" => {
+                    let name = value()?;
+                    options.path =
+                        CursorPath::parse(&name).ok_or(format!("unknown path: {}", name))?;
+                }
+                "--dt" => {
+                    let text = value()?;
+                    options.dt = text
+                        .parse()
+                        .ok()
+                        .filter(|dt: &f64| *dt > 0.0)
+                        .ok_or(format!("expected a positive step size, got {}", text))?;
+                }
+                "--preset" => {
+                    let name = value()?;
+                    options.params =
+                        RopeParams::preset(&name).ok_or(format!("unknown preset: {}", name))?;
+                }
+                "--integrator" => {
+                    let name = value()?;
+                    options.integrator = integrator::by_name(&name)
+                        .ok_or(format!("unknown integrator: {}", name))?;
+                }
+                "--solver" => {
+                    options.solver = match value()?.as_str() {
+                        "springs" => Solver::Springs,
+                        "xpbd" => Solver::xpbd(),
+                        other => return Err(format!("unknown solver: {}", other)),
+                    }
+                }
+                "--gravity" => options.gravity = true,
+                "--anchor-head" => options.anchor_head = true,
+                "--self-collision" => options.self_collision = true,
+                "--floor" => {
+                    let text = value()?;
+                    let y = text
+                        .parse()
+                        .map_err(|_| format!("expected a number, got {}", text))?;
+                    options.floor = Some(y);
+                }
+                "--load" => options.load = Some(value()?),
+                "--save" => options.save = Some(value()?),
+                other => return Err(format!("unknown argument: {}", other)),
+            }
+        }
+        if options.segments == 0 {
+            return Err("--segments must be at least 1".to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn parse_number(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("expected a number, got {}", value))
+}
+
+fn dump(tick: usize, rope: &Rope) {
+    for (i, s) in rope.segments().iter().enumerate() {
+        println!(
+            "{},{},{},{},{},{}",
+            tick,
+            i,
+            s.pos().x,
+            s.pos().y,
+            s.speed().x,
+            s.speed().y
+        );
+    }
+}
+
+fn load(path: &str) -> Result<World, SnapshotError> {
+    let input = BufReader::new(File::open(path)?);
+    if path.ends_with(".json") {
+        World::load_json(input)
+    } else {
+        World::load_binary(input)
+    }
+}
+
+fn save(world: &World, path: &str) -> Result<(), SnapshotError> {
+    let mut out = BufWriter::new(File::create(path)?);
+    if path.ends_with(".json") {
+        world.save_json(&mut out)?;
+    } else {
+        world.save_binary(&mut out)?;
+    }
+    Ok(out.flush()?)
+}
+
+fn build_world(options: Options) -> Result<World, String> {
+    let mut rope = Rope::new(
+        options.segments,
+        options.path.cursor_at(0.0),
+        options.params,
+    )
+    .map_err(|err| err.to_string())?;
+    rope.set_integrator(options.integrator);
+    rope.set_solver(options.solver);
+    rope.set_self_collision(options.self_collision);
+    if options.anchor_head {
+        rope.pin(0, options.path.cursor_at(0.0));
+    }
+
+    let mut env = Environment::new();
+    if options.gravity {
+        env.add_field(ForceField::gravity());
+    }
+    if let Some(y) = options.floor {
+        env.add_collider(Collider::new(Shape::aabb(
+            Vec2::new(0.0, y + 1000.0),
+            Vec2::new(1e6, 1000.0),
+        )));
+    }
+
+    let mut world = World::new(env);
+    world.add_rope(rope);
+    Ok(world)
+}
+
+fn main() {
+    let options = Options::from_args().unwrap_or_else(|err| {
+        eprintln!("headless: {}", err);
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    });
+    let (ticks, every, dt, path) = (options.ticks, options.every, options.dt, options.path);
+    let save_path = options.save.clone();
+
+    let world = match &options.load {
+        Some(load_path) => load(load_path).map_err(|err| format!("{}: {}", load_path, err)),
+        None => build_world(options),
+    };
+    let mut world = world.unwrap_or_else(|err| {
+        eprintln!("headless: {}", err);
+        process::exit(2);
+    });
+    let id = match world.ropes().next() {
+        Some((id, _)) => id,
+        None => {
+            eprintln!("headless: the snapshot has no ropes");
+            process::exit(2);
+        }
+    };
+    // Ticks already run before the snapshot was taken, so the cursor carries on its path.
+    let start = (world.environment().time() / dt).round() as usize;
+
+    println!("tick,segment,x,y,speed_x,speed_y");
+    for tick in 0..ticks {
+        let cursor = path.cursor_at((start + tick) as f64 * dt);
+        let rope = world.rope_mut(id).unwrap();
+        rope.set_cursor(cursor);
+        rope.move_pin(0, cursor);
+        world.step(dt);
+        // Nothing here reacts to events, so they are dropped rather than left to pile up
+        // and end up in a saved snapshot.
+        world.drain_events().for_each(drop);
+        if every != 0 && (tick + 1) % every == 0 && tick + 1 != ticks {
+            dump(start + tick + 1, world.rope(id).unwrap());
+        }
+    }
+    let rope = world.rope(id).unwrap();
+    dump(start + ticks, rope);
+    eprintln!("energy: {}", rope.energy());
+    eprintln!("checksum: {:016x}", world.checksum());
+    if let Some(save_path) = save_path {
+        save(&world, &save_path).unwrap_or_else(|err| {
+            eprintln!("headless: {}: {}", save_path, err);
+            process::exit(1);
+        });
+    }
+}
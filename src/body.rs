@@ -0,0 +1,238 @@
+use crate::collision::{Collider, Shape};
+use crate::environment::Environment;
+use crate::math::{Vec2, ZERO_THRESHOLD};
+use crate::physics::NOMINAL_DT;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BodyId(pub(crate) u64);
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BodyShape {
+    Circle { radius: f64 },
+    Box { half_extents: Vec2 },
+}
+
+/// A 2D rigid body that ropes can hang from, push against and swing.
+///
+/// Speeds are per `NOMINAL_DT` like rope segments, the angular speed in radians per `NOMINAL_DT`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Body {
+    pub(crate) pos: Vec2,
+    pub(crate) prev_pos: Vec2,
+    pub(crate) angle: f64,
+    pub(crate) prev_angle: f64,
+    pub(crate) speed: Vec2,
+    pub(crate) angular_speed: f64,
+    mass: f64,
+    inertia: f64,
+    shape: BodyShape,
+    pub restitution: f64,
+    pub friction: f64,
+    pub(crate) asleep: bool,
+    pub(crate) calm_for: f64,
+    pub(crate) disturbed: bool,
+}
+
+impl Body {
+    pub fn circle(pos: Vec2, radius: f64, mass: f64) -> Body {
+        Body::new(
+            pos,
+            BodyShape::Circle { radius },
+            mass,
+            0.5 * mass * radius * radius,
+        )
+    }
+
+    pub fn rectangle(pos: Vec2, half_extents: Vec2, mass: f64) -> Body {
+        let inertia = mass * (half_extents.dot(half_extents)) / 3.0;
+        Body::new(pos, BodyShape::Box { half_extents }, mass, inertia)
+    }
+
+    fn new(pos: Vec2, shape: BodyShape, mass: f64, inertia: f64) -> Body {
+        assert!(mass > 0.0 && inertia > 0.0, "body mass must be positive");
+        Body {
+            pos,
+            prev_pos: pos,
+            angle: 0.0,
+            prev_angle: 0.0,
+            speed: Vec2::ZERO,
+            angular_speed: 0.0,
+            mass,
+            inertia,
+            shape,
+            restitution: 0.2,
+            friction: 0.5,
+            asleep: false,
+            calm_for: 0.0,
+            disturbed: true,
+        }
+    }
+
+    pub fn pos(&self) -> Vec2 {
+        self.pos
+    }
+
+    pub fn angle(&self) -> f64 {
+        self.angle
+    }
+
+    pub fn speed(&self) -> Vec2 {
+        self.speed
+    }
+
+    pub fn angular_speed(&self) -> f64 {
+        self.angular_speed
+    }
+
+    pub fn mass(&self) -> f64 {
+        self.mass
+    }
+
+    pub fn inertia(&self) -> f64 {
+        self.inertia
+    }
+
+    pub fn shape(&self) -> BodyShape {
+        self.shape
+    }
+
+    pub fn set_pos(&mut self, pos: Vec2) {
+        self.pos = pos;
+        self.disturbed = true;
+    }
+
+    pub fn set_angle(&mut self, angle: f64) {
+        self.angle = angle;
+        self.disturbed = true;
+    }
+
+    pub fn set_speed(&mut self, speed: Vec2) {
+        self.speed = speed;
+        self.disturbed = true;
+    }
+
+    pub fn set_angular_speed(&mut self, angular_speed: f64) {
+        self.angular_speed = angular_speed;
+        self.disturbed = true;
+    }
+
+    /// Position and angle blended between the last two ticks, for rendering between steps.
+    pub fn interpolated(&self, alpha: f64) -> (Vec2, f64) {
+        (
+            self.prev_pos.lerp(self.pos, alpha),
+            self.prev_angle + (self.angle - self.prev_angle) * alpha,
+        )
+    }
+
+    /// Converts a point in the body's frame to world coordinates.
+    pub fn world_point(&self, local: Vec2) -> Vec2 {
+        self.pos + local.rotated(self.angle)
+    }
+
+    /// Unit vector along the body's local x axis.
+    pub fn frame(&self) -> Vec2 {
+        Vec2::new(1.0, 0.0).rotated(self.angle)
+    }
+
+    pub fn velocity_at(&self, point: Vec2) -> Vec2 {
+        self.speed + (point - self.pos).perpendicular() * self.angular_speed
+    }
+
+    pub fn shape_in_world(&self) -> Shape {
+        match self.shape {
+            BodyShape::Circle { radius } => Shape::Circle {
+                center: self.pos,
+                radius,
+            },
+            BodyShape::Box { half_extents } => Shape::Box {
+                center: self.pos,
+                half_extents,
+                angle: self.angle,
+            },
+        }
+    }
+
+    /// Inverse of the mass felt by a push along `direction` at `point`, including the
+    /// part of the push that turns the body instead of moving it.
+    pub fn inverse_mass_at(&self, point: Vec2, direction: Vec2) -> f64 {
+        let arm = (point - self.pos).cross(direction);
+        1.0 / self.mass + arm * arm / self.inertia
+    }
+
+    pub fn apply_impulse(&mut self, impulse: Vec2, point: Vec2) {
+        self.speed += impulse / self.mass;
+        self.angular_speed += (point - self.pos).cross(impulse) / self.inertia;
+        self.disturbed = true;
+    }
+
+    pub fn kinetic_energy(&self) -> f64 {
+        0.5 * self.mass * self.speed.dot(self.speed)
+            + 0.5 * self.inertia * self.angular_speed * self.angular_speed
+    }
+
+    /// Whether the body was put to sleep by its `World`, see `World::set_sleeping`.
+    pub fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+
+    /// Moves and turns the body as if `impulse` acted on `point` for one tick, without
+    /// changing its speed.
+    pub(crate) fn displace(&mut self, impulse: Vec2, point: Vec2) {
+        self.pos += impulse / self.mass;
+        self.angle += (point - self.pos).cross(impulse) / self.inertia;
+    }
+
+    pub(crate) fn tick(&mut self, dt: f64, env: &Environment) {
+        let scale = dt / NOMINAL_DT;
+        self.prev_pos = self.pos;
+        self.prev_angle = self.angle;
+        let force = env.force_at(self.pos, self.speed, self.mass);
+        self.speed += force / self.mass * scale;
+        self.pos += self.speed * scale;
+        self.angle += self.angular_speed * scale;
+        for (_, collider) in env.colliders() {
+            self.collide(collider);
+        }
+    }
+
+    fn collide(&mut self, collider: &Collider) {
+        let probes: Vec<(Vec2, f64)> = match self.shape {
+            BodyShape::Circle { radius } => vec![(self.pos, radius)],
+            BodyShape::Box { .. } => self
+                .shape_in_world()
+                .outline()
+                .into_iter()
+                .map(|corner| (corner, 0.0))
+                .collect(),
+        };
+        for (probe, radius) in probes {
+            let contact = match collider.shape.contact(probe, radius) {
+                Some(contact) => contact,
+                None => continue,
+            };
+            self.pos += contact.normal * contact.depth;
+            let point = probe - contact.normal * radius;
+            let velocity = self.velocity_at(point);
+            let normal_speed = velocity.dot(contact.normal);
+            if normal_speed >= 0.0 {
+                continue;
+            }
+            let restitution = self.restitution.max(collider.restitution);
+            let j =
+                -(1.0 + restitution) * normal_speed / self.inverse_mass_at(point, contact.normal);
+            self.apply_impulse(contact.normal * j, point);
+
+            let velocity = self.velocity_at(point);
+            let tangent_velocity = velocity - contact.normal * velocity.dot(contact.normal);
+            if tangent_velocity.length() < ZERO_THRESHOLD {
+                continue;
+            }
+            let tangent = tangent_velocity.normalized();
+            let friction = (self.friction * collider.friction).sqrt();
+            let jt = (tangent_velocity.length() / self.inverse_mass_at(point, tangent))
+                .min(friction * j);
+            self.apply_impulse(tangent * -jt, point);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::math::Vec2;
+use std::collections::HashMap;
+
+/// Uniform grid that finds pairs of boxes sharing a cell.
+///
+/// Pairs come out sorted so that narrow phase work happens in the same order every run.
+pub struct SpatialHash<K> {
+    cell_size: f64,
+    cells: HashMap<(i64, i64), Vec<K>>,
+}
+
+impl<K: Copy + Ord> SpatialHash<K> {
+    pub fn new(cell_size: f64) -> SpatialHash<K> {
+        SpatialHash {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    pub fn insert(&mut self, key: K, min: Vec2, max: Vec2) {
+        let (x0, y0) = self.cell(min);
+        let (x1, y1) = self.cell(max);
+        for x in x0..=x1 {
+            for y in y0..=y1 {
+                self.cells.entry((x, y)).or_default().push(key);
+            }
+        }
+    }
+
+    /// Every unordered pair of distinct keys that share at least one cell, as `(smaller, larger)`.
+    pub fn pairs(&self) -> Vec<(K, K)> {
+        let mut pairs = Vec::new();
+        for keys in self.cells.values() {
+            for (i, &a) in keys.iter().enumerate() {
+                for &b in &keys[i + 1..] {
+                    if a < b {
+                        pairs.push((a, b));
+                    } else if b < a {
+                        pairs.push((b, a));
+                    }
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    fn cell(&self, pos: Vec2) -> (i64, i64) {
+        (
+            (pos.x / self.cell_size).floor() as i64,
+            (pos.y / self.cell_size).floor() as i64,
+        )
+    }
+}
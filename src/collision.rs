@@ -0,0 +1,297 @@
+use crate::math::{
+    closest_point_on_segment, closest_points_between_segments, Vec2, ZERO_THRESHOLD,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Shape {
+    Circle {
+        center: Vec2,
+        radius: f64,
+    },
+    /// Box rotated by `angle` radians around its center. Axis aligned when `angle` is zero.
+    Box {
+        center: Vec2,
+        half_extents: Vec2,
+        angle: f64,
+    },
+    /// Line segment that segments collide with from either side.
+    Segment {
+        a: Vec2,
+        b: Vec2,
+    },
+    /// Closed polygon, its last point connects back to the first.
+    Polygon {
+        points: Vec<Vec2>,
+    },
+}
+
+/// Where a circle touches a shape: push it `depth` along `normal` to separate them.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Contact {
+    pub normal: Vec2,
+    pub depth: f64,
+}
+
+impl Shape {
+    pub fn aabb(center: Vec2, half_extents: Vec2) -> Shape {
+        Shape::Box {
+            center,
+            half_extents,
+            angle: 0.0,
+        }
+    }
+
+    /// Corners of a box or polygon, in order. Empty for other shapes.
+    pub fn outline(&self) -> Vec<Vec2> {
+        match self {
+            Shape::Box {
+                center,
+                half_extents,
+                angle,
+            } => [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+                .iter()
+                .map(|&(sx, sy)| {
+                    *center + Vec2::new(half_extents.x * sx, half_extents.y * sy).rotated(*angle)
+                })
+                .collect(),
+            Shape::Polygon { points } => points.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn contact(&self, pos: Vec2, radius: f64) -> Option<Contact> {
+        match self {
+            Shape::Circle {
+                center,
+                radius: shape_radius,
+            } => {
+                let offset = pos - *center;
+                let distance = offset.length();
+                if distance >= shape_radius + radius {
+                    return None;
+                }
+                let normal = if distance < ZERO_THRESHOLD {
+                    Vec2::new(0.0, -1.0)
+                } else {
+                    offset / distance
+                };
+                Some(Contact {
+                    normal,
+                    depth: shape_radius + radius - distance,
+                })
+            }
+            Shape::Box {
+                center,
+                half_extents,
+                angle,
+            } => {
+                let local = (pos - *center).rotated(-angle);
+                let contact = box_contact(local, *half_extents, radius)?;
+                Some(Contact {
+                    normal: contact.normal.rotated(*angle),
+                    depth: contact.depth,
+                })
+            }
+            Shape::Segment { a, b } => {
+                let closest = closest_point_on_segment(pos, *a, *b);
+                let offset = pos - closest;
+                let distance = offset.length();
+                if distance >= radius {
+                    return None;
+                }
+                let normal = if distance < ZERO_THRESHOLD {
+                    (*b - *a).normalized().rotated90(true)
+                } else {
+                    offset / distance
+                };
+                Some(Contact {
+                    normal,
+                    depth: radius - distance,
+                })
+            }
+            Shape::Polygon { points } => polygon_contact(points, pos, radius),
+        }
+    }
+}
+
+fn box_contact(local: Vec2, half_extents: Vec2, radius: f64) -> Option<Contact> {
+    let clamped = Vec2::new(
+        local.x.clamp(-half_extents.x, half_extents.x),
+        local.y.clamp(-half_extents.y, half_extents.y),
+    );
+    let offset = local - clamped;
+    let distance = offset.length();
+    if distance >= ZERO_THRESHOLD {
+        if distance >= radius {
+            return None;
+        }
+        return Some(Contact {
+            normal: offset / distance,
+            depth: radius - distance,
+        });
+    }
+    // The center is inside the box: leave through the nearest face.
+    let to_x_face = half_extents.x - local.x.abs();
+    let to_y_face = half_extents.y - local.y.abs();
+    if to_x_face < to_y_face {
+        Some(Contact {
+            normal: Vec2::new(local.x.signum(), 0.0),
+            depth: to_x_face + radius,
+        })
+    } else {
+        Some(Contact {
+            normal: Vec2::new(0.0, local.y.signum()),
+            depth: to_y_face + radius,
+        })
+    }
+}
+
+fn polygon_contact(points: &[Vec2], pos: Vec2, radius: f64) -> Option<Contact> {
+    if points.len() < 2 {
+        return None;
+    }
+    let mut inside = false;
+    let mut nearest: Option<(Vec2, f64)> = None;
+    for i in 0..points.len() {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        if (a.y > pos.y) != (b.y > pos.y) && pos.x < a.x + (pos.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        {
+            inside = !inside;
+        }
+        let closest = closest_point_on_segment(pos, a, b);
+        let distance = (pos - closest).length();
+        if nearest.is_none_or(|(_, d)| distance < d) {
+            nearest = Some((closest, distance));
+        }
+    }
+    let (closest, distance) = nearest?;
+    if inside {
+        if distance < ZERO_THRESHOLD {
+            return None;
+        }
+        Some(Contact {
+            normal: (closest - pos) / distance,
+            depth: distance + radius,
+        })
+    } else if distance < radius && distance >= ZERO_THRESHOLD {
+        Some(Contact {
+            normal: (pos - closest) / distance,
+            depth: radius - distance,
+        })
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Collider {
+    pub shape: Shape,
+    /// Fraction of the normal speed kept when bouncing off, `0..=1`.
+    pub restitution: f64,
+    /// Coulomb friction coefficient scaling how much tangential speed an impact removes.
+    pub friction: f64,
+}
+
+impl Collider {
+    pub fn new(shape: Shape) -> Collider {
+        Collider {
+            shape,
+            restitution: 0.2,
+            friction: 0.5,
+        }
+    }
+
+    /// Moves a circle out of the collider and bounces its speed off the surface.
+    /// Returns whether they were touching.
+    pub fn resolve(&self, pos: &mut Vec2, speed: &mut Vec2, radius: f64) -> bool {
+        let contact = match self.shape.contact(*pos, radius) {
+            Some(contact) => contact,
+            None => return false,
+        };
+        *pos += contact.normal * contact.depth;
+        let normal_speed = speed.dot(contact.normal);
+        if normal_speed < 0.0 {
+            let tangent_speed = *speed - contact.normal * normal_speed;
+            let tangent_speed = tangent_speed.length_sub(-normal_speed * self.friction);
+            *speed = tangent_speed - contact.normal * (normal_speed * self.restitution);
+        }
+        true
+    }
+}
+
+/// Overlap between two capsules with ends `[a0, a1, b0, b1]`. `normal` points from the
+/// second capsule towards the first, `s` and `t` locate the touching point along each.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct CapsuleContact {
+    pub normal: Vec2,
+    pub depth: f64,
+    pub s: f64,
+    pub t: f64,
+}
+
+impl CapsuleContact {
+    pub fn between(ends: [Vec2; 4], radius: f64) -> Option<CapsuleContact> {
+        let [a0, a1, b0, b1] = ends;
+        let (s, t) = closest_points_between_segments(a0, a1, b0, b1);
+        let offset = a0.lerp(a1, s) - b0.lerp(b1, t);
+        let distance = offset.length();
+        if distance >= radius * 2.0 {
+            return None;
+        }
+        let normal = if distance >= ZERO_THRESHOLD {
+            offset / distance
+        } else if (a1 - a0).length() >= ZERO_THRESHOLD {
+            (a1 - a0).normalized().rotated90(true)
+        } else {
+            Vec2::new(1.0, 0.0)
+        };
+        Some(CapsuleContact {
+            normal,
+            depth: radius * 2.0 - distance,
+            s,
+            t,
+        })
+    }
+
+    /// Splits a separation of `amount` along the normal between the four ends, in
+    /// proportion to their inverse mass and how close they are to the touching point.
+    pub fn distribute(&self, inverse_masses: [f64; 4], amount: f64) -> [Vec2; 4] {
+        let weights = [1.0 - self.s, self.s, -(1.0 - self.t), -self.t];
+        let denominator: f64 = weights
+            .iter()
+            .zip(&inverse_masses)
+            .map(|(weight, w)| weight * weight * w)
+            .sum();
+        let mut deltas = [Vec2::ZERO; 4];
+        if denominator < ZERO_THRESHOLD {
+            return deltas;
+        }
+        let lambda = amount / denominator;
+        for (delta, (weight, w)) in deltas.iter_mut().zip(weights.iter().zip(&inverse_masses)) {
+            *delta = self.normal * (lambda * weight * w);
+        }
+        deltas
+    }
+
+    /// Speed of the first capsule's touching point towards the second, along the normal.
+    pub fn approach_speed(&self, speeds: [Vec2; 4]) -> f64 {
+        let a = speeds[0].lerp(speeds[1], self.s);
+        let b = speeds[2].lerp(speeds[3], self.t);
+        -(a - b).dot(self.normal)
+    }
+
+    /// Pushes the capsules apart and removes the speed they approach each other with.
+    /// Returns the position and speed changes for each end.
+    pub fn resolve(&self, speeds: [Vec2; 4], inverse_masses: [f64; 4]) -> ([Vec2; 4], [Vec2; 4]) {
+        let pos_deltas = self.distribute(inverse_masses, self.depth);
+        let approach = self.approach_speed(speeds);
+        let speed_deltas = if approach > 0.0 {
+            self.distribute(inverse_masses, approach)
+        } else {
+            [Vec2::ZERO; 4]
+        };
+        (pos_deltas, speed_deltas)
+    }
+}
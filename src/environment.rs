@@ -0,0 +1,117 @@
+use crate::collision::Collider;
+use crate::field::ForceField;
+use crate::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FieldId(pub(crate) usize);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ColliderId(pub(crate) usize);
+
+/// World-level state shared by every rope: elapsed time, force fields and static colliders.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Environment {
+    time: f64,
+    fields: Vec<Option<(ForceField, bool)>>,
+    colliders: Vec<Option<Collider>>,
+}
+
+impl Environment {
+    pub fn new() -> Environment {
+        Environment::default()
+    }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn advance(&mut self, dt: f64) {
+        self.time += dt;
+    }
+
+    pub fn add_field(&mut self, field: ForceField) -> FieldId {
+        self.fields.push(Some((field, true)));
+        FieldId(self.fields.len() - 1)
+    }
+
+    pub fn remove_field(&mut self, id: FieldId) -> Option<ForceField> {
+        self.fields
+            .get_mut(id.0)
+            .and_then(Option::take)
+            .map(|(field, _)| field)
+    }
+
+    pub fn field(&self, id: FieldId) -> Option<&ForceField> {
+        self.slot(id).map(|(field, _)| field)
+    }
+
+    pub fn field_mut(&mut self, id: FieldId) -> Option<&mut ForceField> {
+        self.slot_mut(id).map(|(field, _)| field)
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = (FieldId, &ForceField)> {
+        self.fields
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.as_ref().map(|(field, _)| (FieldId(i), field)))
+    }
+
+    pub fn is_field_enabled(&self, id: FieldId) -> bool {
+        self.slot(id).is_some_and(|&(_, enabled)| enabled)
+    }
+
+    pub fn set_field_enabled(&mut self, id: FieldId, enabled: bool) {
+        if let Some((_, slot_enabled)) = self.slot_mut(id) {
+            *slot_enabled = enabled;
+        }
+    }
+
+    pub fn toggle_field(&mut self, id: FieldId) {
+        let enabled = self.is_field_enabled(id);
+        self.set_field_enabled(id, !enabled);
+    }
+
+    pub fn add_collider(&mut self, collider: Collider) -> ColliderId {
+        self.colliders.push(Some(collider));
+        ColliderId(self.colliders.len() - 1)
+    }
+
+    pub fn remove_collider(&mut self, id: ColliderId) -> Option<Collider> {
+        self.colliders.get_mut(id.0).and_then(Option::take)
+    }
+
+    pub fn collider(&self, id: ColliderId) -> Option<&Collider> {
+        self.colliders.get(id.0).and_then(Option::as_ref)
+    }
+
+    pub fn collider_mut(&mut self, id: ColliderId) -> Option<&mut Collider> {
+        self.colliders.get_mut(id.0).and_then(Option::as_mut)
+    }
+
+    pub fn colliders(&self) -> impl Iterator<Item = (ColliderId, &Collider)> {
+        self.colliders
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.as_ref().map(|collider| (ColliderId(i), collider)))
+    }
+
+    /// Sum of all enabled fields acting on a segment.
+    pub fn force_at(&self, pos: Vec2, speed: Vec2, mass: f64) -> Vec2 {
+        let mut force = Vec2::ZERO;
+        for (field, enabled) in self.fields.iter().flatten() {
+            if *enabled {
+                force += field.force_at(pos, speed, mass, self.time);
+            }
+        }
+        force
+    }
+
+    fn slot(&self, id: FieldId) -> Option<&(ForceField, bool)> {
+        self.fields.get(id.0).and_then(Option::as_ref)
+    }
+
+    fn slot_mut(&mut self, id: FieldId) -> Option<&mut (ForceField, bool)> {
+        self.fields.get_mut(id.0).and_then(Option::as_mut)
+    }
+}
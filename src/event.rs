@@ -0,0 +1,56 @@
+use crate::environment::ColliderId;
+use crate::world::RopeId;
+use serde::{Deserialize, Serialize};
+
+/// Something that happened to a single rope during `Rope::tick`.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RopeEvent {
+    /// Link `link` went above the rope's breaking tension.
+    TensionExceeded { link: usize, tension: f64 },
+    /// Link `link` stayed above the breaking tension long enough to snap. `World` cuts
+    /// the rope there; a rope stepped on its own is left for the caller to split.
+    LinkBroken { link: usize },
+    /// Segment `segment` started touching a collider.
+    ContactBegan {
+        segment: usize,
+        collider: ColliderId,
+    },
+    /// Segment `segment` stopped touching a collider.
+    ContactEnded {
+        segment: usize,
+        collider: ColliderId,
+    },
+    /// Every segment's speed dropped below `ZERO_THRESHOLD`, where static friction holds
+    /// it in place.
+    CameToRest,
+}
+
+/// Something that happened during `World::step` that a game may want to react to.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Event {
+    TensionExceeded {
+        rope: RopeId,
+        link: usize,
+        tension: f64,
+    },
+    /// Link `link` of `rope` snapped. `rope` keeps the segments up to `link` and the
+    /// rest became `tail`.
+    LinkBroken {
+        rope: RopeId,
+        link: usize,
+        tail: RopeId,
+    },
+    ContactBegan {
+        rope: RopeId,
+        segment: usize,
+        collider: ColliderId,
+    },
+    ContactEnded {
+        rope: RopeId,
+        segment: usize,
+        collider: ColliderId,
+    },
+    CameToRest {
+        rope: RopeId,
+    },
+}
@@ -0,0 +1,83 @@
+use crate::math::Vec2;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// A force applied to every rope segment in the environment.
+///
+/// Speeds and accelerations are in the same per-`NOMINAL_DT` units as the rope itself.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ForceField {
+    /// Constant acceleration, independent of segment mass.
+    Gravity(Vec2),
+    /// Drags segments towards `velocity`, scaled by a gust factor that varies over `gust_period` seconds.
+    Wind {
+        velocity: Vec2,
+        drag: f64,
+        gust_strength: f64,
+        gust_period: f64,
+    },
+    /// Pulls segments towards `center`, or pushes them away when `strength` is negative.
+    /// Fades out linearly and stops at `radius`.
+    Radial {
+        center: Vec2,
+        strength: f64,
+        radius: f64,
+    },
+    /// Pushes segments around `center`, clockwise on screen for positive `strength`.
+    Vortex {
+        center: Vec2,
+        strength: f64,
+        radius: f64,
+    },
+}
+
+impl ForceField {
+    pub fn gravity() -> ForceField {
+        ForceField::Gravity(Vec2::new(0.0, 0.004))
+    }
+
+    pub fn force_at(&self, pos: Vec2, speed: Vec2, mass: f64, time: f64) -> Vec2 {
+        match *self {
+            ForceField::Gravity(acceleration) => acceleration * mass,
+            ForceField::Wind {
+                velocity,
+                drag,
+                gust_strength,
+                gust_period,
+            } => {
+                let gust = if gust_period > 0.0 {
+                    let phase = time / gust_period * 2.0 * PI;
+                    phase.sin() * 0.5 + (phase * 2.3).sin() * 0.3 + (phase * 5.1).sin() * 0.2
+                } else {
+                    0.0
+                };
+                (velocity * (1.0 + gust_strength * gust) - speed) * drag
+            }
+            ForceField::Radial {
+                center,
+                strength,
+                radius,
+            } => match falloff(center - pos, radius) {
+                Some((dir, weight)) => dir * (strength * weight),
+                None => Vec2::ZERO,
+            },
+            ForceField::Vortex {
+                center,
+                strength,
+                radius,
+            } => match falloff(pos - center, radius) {
+                Some((dir, weight)) => dir.perpendicular() * (strength * weight),
+                None => Vec2::ZERO,
+            },
+        }
+    }
+}
+
+fn falloff(offset: Vec2, radius: f64) -> Option<(Vec2, f64)> {
+    let distance = offset.length();
+    if distance >= radius || distance == 0.0 {
+        None
+    } else {
+        Some((offset / distance, 1.0 - distance / radius))
+    }
+}
@@ -0,0 +1,71 @@
+use crate::math::Vec2;
+use sdl2::event::Event;
+
+pub struct InputAggregator {
+    window_size: Vec2,
+    active_finger: Option<i64>,
+    cursor: Vec2,
+}
+
+impl InputAggregator {
+    const MOUSE_SENSITIVITY: f64 = 0.5;
+
+    pub fn new(window_size: Vec2) -> InputAggregator {
+        InputAggregator {
+            window_size,
+            active_finger: None,
+            cursor: window_size / 2.0,
+        }
+    }
+
+    pub fn cursor(&self) -> Vec2 {
+        self.cursor
+    }
+
+    pub fn window_size(&self) -> Vec2 {
+        self.window_size
+    }
+
+    pub fn process_event(&mut self, event: Event) {
+        if let Some(active_finger_id) = self.active_finger {
+            match event {
+                Event::FingerMotion {
+                    finger_id, x, y, ..
+                } if finger_id == active_finger_id => {
+                    self.set_cursor_from_finger_position(x, y);
+                }
+                Event::FingerUp {
+                    finger_id, x, y, ..
+                } if finger_id == active_finger_id => {
+                    self.active_finger = None;
+                    self.set_cursor_from_finger_position(x, y);
+                }
+                _ => {}
+            }
+        } else {
+            match event {
+                Event::FingerDown {
+                    finger_id, x, y, ..
+                } => {
+                    self.active_finger = Some(finger_id);
+                    self.set_cursor_from_finger_position(x, y);
+                }
+                Event::MouseMotion { xrel, yrel, .. } => {
+                    self.cursor += Vec2 {
+                        x: xrel.into(),
+                        y: yrel.into(),
+                    } * Self::MOUSE_SENSITIVITY;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn set_cursor_from_finger_position(&mut self, x: f32, y: f32) {
+        // Finger positions are always in range of 0..1
+        self.cursor = Vec2 {
+            x: x as f64 * self.window_size.x,
+            y: y as f64 * self.window_size.y,
+        };
+    }
+}
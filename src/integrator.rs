@@ -0,0 +1,171 @@
+use crate::math::{Vec2, ZERO_THRESHOLD};
+use crate::params::RopeParams;
+use crate::physics::{RopeSegment, NOMINAL_DT};
+
+/// Writes the total force acting on every segment of the given rope state.
+pub type ForceFn<'a> = dyn Fn(&[RopeSegment], &mut [Vec2]) + 'a;
+
+/// Advances the positions and speeds of a rope by one step of `dt` seconds.
+///
+/// `forces` may be evaluated as many times as the scheme needs, on intermediate states.
+pub trait Integrator: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn step(&self, segments: &mut [RopeSegment], params: &RopeParams, dt: f64, forces: &ForceFn);
+}
+
+pub const NAMES: [&str; 3] = ["euler", "verlet", "rk4"];
+
+pub fn by_name(name: &str) -> Option<Box<dyn Integrator>> {
+    match name {
+        "euler" => Some(Box::new(SemiImplicitEuler)),
+        "verlet" => Some(Box::new(PositionVerlet)),
+        "rk4" => Some(Box::new(Rk4)),
+        _ => None,
+    }
+}
+
+/// Stores an integrator as its name, so only the built-in ones can be saved.
+pub(crate) mod by_name_serde {
+    use super::Integrator;
+    use serde::de::{self, Deserialize, Deserializer};
+    use serde::ser::{self, Serializer};
+    use std::sync::Arc;
+
+    pub fn serialize<S: Serializer>(
+        integrator: &Arc<dyn Integrator>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let name = integrator.name();
+        if super::by_name(name).is_none() {
+            return Err(ser::Error::custom(format!(
+                "cannot save integrator {}",
+                name
+            )));
+        }
+        serializer.serialize_str(name)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Arc<dyn Integrator>, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        super::by_name(&name)
+            .map(Arc::from)
+            .ok_or_else(|| de::Error::custom(format!("unknown integrator {}", name)))
+    }
+}
+
+/// Symplectic Euler with speed and force caps, the original integrator.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SemiImplicitEuler;
+
+impl Integrator for SemiImplicitEuler {
+    fn name(&self) -> &'static str {
+        "euler"
+    }
+
+    fn step(&self, segments: &mut [RopeSegment], params: &RopeParams, dt: f64, forces: &ForceFn) {
+        let mut total = vec![Vec2::ZERO; segments.len()];
+        forces(segments, &mut total);
+        for (segment, force) in segments.iter_mut().zip(total) {
+            segment.force = force;
+            segment.tick(params, dt);
+        }
+    }
+}
+
+/// Drift-kick-drift position Verlet. Second order at one force evaluation per step.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct PositionVerlet;
+
+impl Integrator for PositionVerlet {
+    fn name(&self) -> &'static str {
+        "verlet"
+    }
+
+    fn step(&self, segments: &mut [RopeSegment], params: &RopeParams, dt: f64, forces: &ForceFn) {
+        let scale = dt / NOMINAL_DT;
+        let mut mid = segments.to_vec();
+        for s in &mut mid {
+            s.pos += s.speed * (scale / 2.0);
+        }
+        let mut accel = vec![Vec2::ZERO; segments.len()];
+        accelerations(&mid, params, forces, &mut accel);
+        for ((segment, mid), a) in segments.iter_mut().zip(&mid).zip(accel) {
+            let speed = segment.speed + a * scale;
+            segment.speed = limit_speed(speed, segment.speed, params, scale);
+            segment.pos = mid.pos + segment.speed * (scale / 2.0);
+            segment.force = Vec2::ZERO;
+        }
+    }
+}
+
+/// Classic fourth order Runge-Kutta. Four force evaluations per step.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Rk4;
+
+impl Integrator for Rk4 {
+    fn name(&self) -> &'static str {
+        "rk4"
+    }
+
+    fn step(&self, segments: &mut [RopeSegment], params: &RopeParams, dt: f64, forces: &ForceFn) {
+        let scale = dt / NOMINAL_DT;
+        let n = segments.len();
+        let mut state = segments.to_vec();
+        let mut speeds = [
+            vec![Vec2::ZERO; n],
+            vec![Vec2::ZERO; n],
+            vec![Vec2::ZERO; n],
+            vec![Vec2::ZERO; n],
+        ];
+        let mut accels = [
+            vec![Vec2::ZERO; n],
+            vec![Vec2::ZERO; n],
+            vec![Vec2::ZERO; n],
+            vec![Vec2::ZERO; n],
+        ];
+        for stage in 0..4 {
+            if stage > 0 {
+                let h = if stage == 3 { scale } else { scale / 2.0 };
+                for i in 0..n {
+                    state[i].pos = segments[i].pos + speeds[stage - 1][i] * h;
+                    state[i].speed = segments[i].speed + accels[stage - 1][i] * h;
+                }
+            }
+            for i in 0..n {
+                speeds[stage][i] = state[i].speed;
+            }
+            accelerations(&state, params, forces, &mut accels[stage]);
+        }
+        for (i, segment) in segments.iter_mut().enumerate() {
+            let weighted =
+                |k: &[Vec<Vec2>; 4]| (k[0][i] + k[1][i] * 2.0 + k[2][i] * 2.0 + k[3][i]) / 6.0;
+            let speed = segment.speed + weighted(&accels) * scale;
+            segment.pos += weighted(&speeds) * scale;
+            segment.speed = limit_speed(speed, segment.speed, params, scale);
+            segment.force = Vec2::ZERO;
+        }
+    }
+}
+
+fn accelerations(state: &[RopeSegment], params: &RopeParams, forces: &ForceFn, out: &mut [Vec2]) {
+    forces(state, out);
+    for (s, a) in state.iter().zip(out.iter_mut()) {
+        let mut force = *a;
+        if s.speed.length() < ZERO_THRESHOLD {
+            force = force.length_sub(params.static_friction);
+        }
+        *a = force.length_clamped(params.force_cap) / params.mass;
+    }
+}
+
+fn limit_speed(speed: Vec2, prev_speed: Vec2, params: &RopeParams, scale: f64) -> Vec2 {
+    let speed = if prev_speed.length() < ZERO_THRESHOLD {
+        speed
+    } else {
+        speed.length_sub(params.kinetic_friction / params.mass * scale)
+    };
+    speed.length_clamped(params.speed_cap)
+}
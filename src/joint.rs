@@ -0,0 +1,134 @@
+use crate::body::BodyId;
+use crate::math::{Vec2, ZERO_THRESHOLD};
+use crate::physics::RopeSegment;
+use crate::world::RopeId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct JointId(pub(crate) u64);
+
+/// One side of a joint.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum JointEnd {
+    Segment {
+        rope: RopeId,
+        index: usize,
+    },
+    /// Point on a rigid body, in the body's own frame.
+    Body {
+        body: BodyId,
+        local: Vec2,
+    },
+    /// Immovable point in the world.
+    Anchor(Vec2),
+}
+
+/// How a joint constrains end `b` relative to end `a`.
+///
+/// Offsets and axes are given in `a`'s frame: along its rope when `a` is a segment and
+/// the body's own frame when it is a body, so they turn with it, and in world
+/// coordinates when `a` is an anchor.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum JointKind {
+    /// Holds `b` at `offset` from `a`.
+    Fixed { offset: Vec2 },
+    /// Keeps `b` exactly `length` away from `a` and lets it swing freely around it.
+    Revolute { length: f64 },
+    /// Lets `b` slide along `axis` through `a`, between `min` and `max` along it.
+    Slider { axis: Vec2, min: f64, max: f64 },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Joint {
+    pub a: JointEnd,
+    pub b: JointEnd,
+    pub kind: JointKind,
+}
+
+impl Joint {
+    pub fn fixed(a: JointEnd, b: JointEnd, offset: Vec2) -> Joint {
+        Joint {
+            a,
+            b,
+            kind: JointKind::Fixed { offset },
+        }
+    }
+
+    pub fn revolute(a: JointEnd, b: JointEnd) -> Joint {
+        Joint {
+            a,
+            b,
+            kind: JointKind::Revolute { length: 0.0 },
+        }
+    }
+
+    pub fn slider(a: JointEnd, b: JointEnd, axis: Vec2, min: f64, max: f64) -> Joint {
+        Joint {
+            a,
+            b,
+            kind: JointKind::Slider { axis, min, max },
+        }
+    }
+
+    pub fn involves(&self, rope: RopeId) -> bool {
+        [self.a, self.b]
+            .iter()
+            .any(|end| matches!(end, JointEnd::Segment { rope: r, .. } if *r == rope))
+    }
+
+    pub fn involves_body(&self, body: BodyId) -> bool {
+        [self.a, self.b]
+            .iter()
+            .any(|end| matches!(end, JointEnd::Body { body: b, .. } if *b == body))
+    }
+
+    /// Whether the joint can be solved: a slider's `min` is at most its `max` and neither
+    /// is NaN.
+    pub fn is_valid(&self) -> bool {
+        match self.kind {
+            JointKind::Slider { min, max, .. } => min <= max,
+            _ => true,
+        }
+    }
+
+    /// How far `b` is from where the joint wants it, given `a`'s position and frame.
+    pub(crate) fn error(&self, a: Vec2, a_frame: Vec2, b: Vec2) -> Vec2 {
+        let to_world = |local: Vec2| a_frame * local.x + a_frame.perpendicular() * local.y;
+        match self.kind {
+            JointKind::Fixed { offset } => b - (a + to_world(offset)),
+            JointKind::Revolute { length } => {
+                let offset = b - a;
+                let distance = offset.length();
+                if distance < ZERO_THRESHOLD {
+                    return Vec2::ZERO;
+                }
+                offset / distance * (distance - length)
+            }
+            JointKind::Slider { axis, min, max } => {
+                if axis.length() < ZERO_THRESHOLD {
+                    return b - a;
+                }
+                let axis = to_world(axis.normalized());
+                let along = (b - a).dot(axis).clamp(min, max);
+                b - (a + axis * along)
+            }
+        }
+    }
+}
+
+/// Unit vector along the rope at `index`, pointing towards the tail.
+pub(crate) fn frame_at(segments: &[RopeSegment], index: usize) -> Vec2 {
+    let (from, to) = if index + 1 < segments.len() {
+        (index, index + 1)
+    } else if index > 0 {
+        (index - 1, index)
+    } else {
+        return Vec2::new(1.0, 0.0);
+    };
+    let direction = segments[to].pos() - segments[from].pos();
+    if direction.length() < ZERO_THRESHOLD {
+        Vec2::new(1.0, 0.0)
+    } else {
+        direction.normalized()
+    }
+}
@@ -0,0 +1,180 @@
+use crate::math::{Vec2, ZERO_THRESHOLD};
+use std::f64::consts::PI;
+
+/// A curve to lay a new rope out along. Angles are in radians, clockwise on screen.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RopeShape {
+    Line {
+        from: Vec2,
+        to: Vec2,
+    },
+    Polyline {
+        points: Vec<Vec2>,
+    },
+    Arc {
+        center: Vec2,
+        radius: f64,
+        from: f64,
+        to: f64,
+    },
+    /// Archimedean spiral winding outwards from `inner_radius` at `angle`.
+    Spiral {
+        center: Vec2,
+        inner_radius: f64,
+        outer_radius: f64,
+        turns: f64,
+        angle: f64,
+    },
+    /// Cubic Bézier curve.
+    Bezier {
+        from: Vec2,
+        controls: [Vec2; 2],
+        to: Vec2,
+    },
+}
+
+impl RopeShape {
+    /// Upper bound on the pieces a curve is approximated with.
+    const MAX_PIECES: usize = 1 << 16;
+
+    pub fn start(&self) -> Option<Vec2> {
+        match self {
+            RopeShape::Polyline { points } => points.first().copied(),
+            _ => Some(self.point_at(0.0)),
+        }
+    }
+
+    /// `n` points spread evenly by length from the start of the curve to its end.
+    pub fn points(&self, n: usize) -> Vec<Vec2> {
+        const PIECES_PER_POINT: usize = 16;
+        let start = match self.start() {
+            Some(start) => start,
+            None => return Vec::new(),
+        };
+        if n < 2 {
+            return vec![start; n];
+        }
+        let outline = self.outline(self.rough_length() / ((n - 1) * PIECES_PER_POINT) as f64);
+        if outline.len() < 2 {
+            return vec![start; n];
+        }
+        let mut distances = vec![0.0];
+        for pair in outline.windows(2) {
+            distances.push(distances.last().unwrap() + (pair[1] - pair[0]).length());
+        }
+        let total = distances[outline.len() - 1];
+        let mut j = 0;
+        (0..n)
+            .map(|i| {
+                let target = total * i as f64 / (n - 1) as f64;
+                while j + 2 < outline.len() && distances[j + 1] < target {
+                    j += 1;
+                }
+                let span = distances[j + 1] - distances[j];
+                let t = if span > 0.0 {
+                    ((target - distances[j]) / span).min(1.0)
+                } else {
+                    0.0
+                };
+                outline[j].lerp(outline[j + 1], t)
+            })
+            .collect()
+    }
+
+    /// Points along the curve exactly `spacing` apart in a straight line, starting at its
+    /// start and stopping at the last one that fits, less than `spacing` from its end.
+    pub fn points_at_spacing(&self, spacing: f64) -> Vec<Vec2> {
+        assert!(spacing > 0.0, "spacing must be positive");
+        let outline = self.outline(spacing / 8.0);
+        let mut from = match outline.first() {
+            Some(&start) => start,
+            None => return Vec::new(),
+        };
+        // Keeps a point that lands on the end of the curve from being lost to rounding.
+        let reach = spacing * (1.0 - 1e-9);
+        let mut points = vec![from];
+        let mut piece_start = from;
+        for &piece_end in &outline[1..] {
+            // `piece_start` is always within `spacing` of `from`, so each crossing of the
+            // circle around `from` is where the next point goes.
+            while (piece_end - from).length() >= reach {
+                let d = piece_end - piece_start;
+                let f = piece_start - from;
+                let a = d.dot(d);
+                let b = f.dot(d);
+                let c = f.dot(f) - spacing * spacing;
+                let t = (-b + (b * b - a * c).max(0.0).sqrt()) / a;
+                from = piece_start + d * t.clamp(0.0, 1.0);
+                piece_start = from;
+                points.push(from);
+            }
+            piece_start = piece_end;
+        }
+        points
+    }
+
+    /// Point a fraction `t` of the way along the curve, by parameter rather than length.
+    fn point_at(&self, t: f64) -> Vec2 {
+        match *self {
+            RopeShape::Line { from, to } => from.lerp(to, t),
+            RopeShape::Polyline { .. } => unreachable!("polylines are used as they are"),
+            RopeShape::Arc {
+                center,
+                radius,
+                from,
+                to,
+            } => center + Vec2::new(radius, 0.0).rotated(from + (to - from) * t),
+            RopeShape::Spiral {
+                center,
+                inner_radius,
+                outer_radius,
+                turns,
+                angle,
+            } => {
+                let radius = inner_radius + (outer_radius - inner_radius) * t;
+                center + Vec2::new(radius, 0.0).rotated(angle + turns * 2.0 * PI * t)
+            }
+            RopeShape::Bezier { from, controls, to } => {
+                let s = 1.0 - t;
+                from * (s * s * s)
+                    + controls[0] * (3.0 * s * s * t)
+                    + controls[1] * (3.0 * s * t * t)
+                    + to * (t * t * t)
+            }
+        }
+    }
+
+    fn rough_length(&self) -> f64 {
+        match self {
+            RopeShape::Line { from, to } => (*to - *from).length(),
+            RopeShape::Polyline { points } => polyline_length(points),
+            _ => polyline_length(&self.sample(64)),
+        }
+    }
+
+    /// The curve as a polyline with pieces about `max_piece` long at most. Lines and
+    /// polylines are returned as they are.
+    fn outline(&self, max_piece: f64) -> Vec<Vec2> {
+        match self {
+            RopeShape::Line { from, to } => vec![*from, *to],
+            RopeShape::Polyline { points } => points.clone(),
+            _ => {
+                let pieces = (self.rough_length() / max_piece.max(ZERO_THRESHOLD)).ceil();
+                self.sample((pieces as usize).clamp(64, RopeShape::MAX_PIECES))
+            }
+        }
+    }
+
+    fn sample(&self, pieces: usize) -> Vec<Vec2> {
+        (0..=pieces)
+            .map(|i| self.point_at(i as f64 / pieces as f64))
+            .collect()
+    }
+}
+
+fn polyline_length(points: &[Vec2]) -> f64 {
+    points
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).length())
+        .sum()
+}
@@ -0,0 +1,46 @@
+//! Rope simulation library.
+//!
+//! The physics modules build without SDL2. Rendering and input handling for the
+//! demo (`render`, `input`) live behind the `sdl2` feature, which is off by default.
+
+pub mod body;
+pub mod broadphase;
+pub mod collision;
+pub mod environment;
+pub mod event;
+pub mod field;
+pub mod integrator;
+pub mod joint;
+pub mod layout;
+pub mod math;
+pub mod params;
+pub mod physics;
+pub mod recording;
+pub mod scene;
+pub mod snapshot;
+pub mod solver;
+pub mod timestep;
+pub mod world;
+
+#[cfg(feature = "sdl2")]
+pub mod input;
+#[cfg(feature = "sdl2")]
+pub mod render;
+
+pub use body::{Body, BodyId, BodyShape};
+pub use collision::{Collider, Shape};
+pub use environment::{ColliderId, Environment, FieldId};
+pub use event::{Event, RopeEvent};
+pub use field::ForceField;
+pub use integrator::Integrator;
+pub use joint::{Joint, JointEnd, JointId, JointKind};
+pub use layout::RopeShape;
+pub use math::Vec2;
+pub use params::{ParamsError, RopeParams};
+pub use physics::{BreakingLimit, Pin, Rope, RopeSegment, Winch, NOMINAL_DT};
+pub use recording::{Button, Input, Recording, RecordingError};
+pub use scene::{Scene, SceneError, SceneField, SceneRope};
+pub use snapshot::{SnapshotError, SNAPSHOT_VERSION};
+pub use solver::Solver;
+pub use timestep::FixedTimestep;
+pub use world::{RopeEnd, RopeId, SleepThreshold, World};
@@ -1,328 +1,514 @@
 extern crate sdl2;
 
+use rope_sim::input::InputAggregator;
+use rope_sim::{
+    integrator, Body, BreakingLimit, Button, Collider, Environment, FieldId, FixedTimestep,
+    ForceField, Input, Integrator, Joint, JointEnd, Recording, Rope, RopeId, RopeParams, Scene,
+    Shape, SleepThreshold, Solver, Vec2, Winch, World, NOMINAL_DT,
+};
 use sdl2::event::Event;
 use sdl2::gfx::primitives::DrawRenderer;
 use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
 use sdl2::pixels::Color;
 use sdl2::render::Canvas;
 use sdl2::video::Window;
-use std::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
-use std::time::Duration;
-
-const ZERO_THRESHOLD: f64 = 0.00001;
-
-const SPEED_CAP: f64 = 100.0;
-const FORCE_CAP: f64 = 50.0;
-
-#[derive(Debug, Copy, Clone)]
-struct Vec2 {
-    x: f64,
-    y: f64,
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::process;
+use std::time::{Duration, Instant, SystemTime};
+
+fn preset_for_key(keycode: Keycode) -> Option<RopeParams> {
+    let index = match keycode {
+        Keycode::Num1 => 0,
+        Keycode::Num2 => 1,
+        Keycode::Num3 => 2,
+        Keycode::Num4 => 3,
+        _ => return None,
+    };
+    RopeParams::preset(RopeParams::PRESETS[index])
 }
 
-impl Vec2 {
-    const ZERO: Vec2 = Vec2 { x: 0.0, y: 0.0 };
+fn next_integrator(current: &dyn Integrator) -> Box<dyn Integrator> {
+    let names = &integrator::NAMES;
+    let index = names.iter().position(|&name| name == current.name());
+    let next = index.map_or(0, |i| (i + 1) % names.len());
+    integrator::by_name(names[next]).unwrap()
+}
 
-    fn length(self) -> f64 {
-        (self.x.powf(2.0) + self.y.powf(2.0)).sqrt()
-    }
+/// Adds the demo's force fields, all disabled, and returns the key toggling each one.
+fn add_demo_fields(env: &mut Environment, window_size: Vec2) -> Vec<(Keycode, FieldId)> {
+    let center = window_size / 2.0;
+    let radius = window_size.y / 2.0;
+    let fields = vec![
+        (Keycode::G, ForceField::gravity()),
+        (
+            Keycode::W,
+            ForceField::Wind {
+                velocity: Vec2::new(0.5, 0.0),
+                drag: 0.01,
+                gust_strength: 0.8,
+                gust_period: 3.0,
+            },
+        ),
+        (
+            Keycode::A,
+            ForceField::Radial {
+                center,
+                strength: 0.01,
+                radius,
+            },
+        ),
+        (
+            Keycode::R,
+            ForceField::Radial {
+                center,
+                strength: -0.01,
+                radius,
+            },
+        ),
+        (
+            Keycode::V,
+            ForceField::Vortex {
+                center,
+                strength: 0.01,
+                radius,
+            },
+        ),
+    ];
+    fields
+        .into_iter()
+        .map(|(keycode, field)| {
+            let id = env.add_field(field);
+            env.set_field_enabled(id, false);
+            (keycode, id)
+        })
+        .collect()
+}
 
-    fn normalized(self) -> Vec2 {
-        self / self.length()
+/// Walls just outside the window edges plus a few obstacles to drape the rope over.
+fn add_demo_colliders(env: &mut Environment, window_size: Vec2) {
+    let (w, h) = (window_size.x, window_size.y);
+    let thickness = 50.0;
+    let walls = [
+        (Vec2::new(w / 2.0, -thickness), Vec2::new(w, thickness)),
+        (Vec2::new(w / 2.0, h + thickness), Vec2::new(w, thickness)),
+        (Vec2::new(-thickness, h / 2.0), Vec2::new(thickness, h)),
+        (Vec2::new(w + thickness, h / 2.0), Vec2::new(thickness, h)),
+    ];
+    for &(center, half_extents) in &walls {
+        env.add_collider(Collider::new(Shape::aabb(center, half_extents)));
     }
+    env.add_collider(Collider::new(Shape::Circle {
+        center: Vec2::new(w * 0.25, h * 0.6),
+        radius: h * 0.08,
+    }));
+    env.add_collider(Collider::new(Shape::Box {
+        center: Vec2::new(w * 0.75, h * 0.6),
+        half_extents: Vec2::new(h * 0.12, h * 0.03),
+        angle: 0.4,
+    }));
+    env.add_collider(Collider::new(Shape::Polygon {
+        points: vec![
+            Vec2::new(w * 0.45, h * 0.9),
+            Vec2::new(w * 0.55, h * 0.9),
+            Vec2::new(w * 0.5, h * 0.75),
+        ],
+    }));
+    env.add_collider(Collider::new(Shape::Segment {
+        a: Vec2::new(w * 0.1, h * 0.25),
+        b: Vec2::new(w * 0.3, h * 0.3),
+    }));
+}
 
-    fn length_sub(self, amnt: f64) -> Vec2 {
-        if self.length() < amnt || self.length() < ZERO_THRESHOLD {
-            Vec2::ZERO
-        } else {
-            self - self.normalized() * amnt
-        }
-    }
+/// A 40-segment rope hanging straight down from `pos`.
+fn spawn_rope(pos: Vec2) -> Rope {
+    let params = RopeParams::default();
+    let end = pos + Vec2::new(0.0, params.length * 39.0);
+    let mut rope = Rope::along_line(pos, end, params).unwrap();
+    rope.set_self_collision(true);
+    rope.set_cursor(pos);
+    rope
+}
 
-    fn length_clamped(self, amnt: f64) -> Vec2 {
-        if self.length() < amnt {
-            self
-        } else {
-            self.normalized() * amnt
+fn handle_rope_key(rope: &mut Rope, keycode: Keycode, cursor: Vec2) {
+    const WINCH_SPEED: f64 = 0.1;
+    const WINCH_MAX_LENGTH: f64 = 2000.0;
+    match keycode {
+        Keycode::Up => rope.set_winch(Winch {
+            speed: -WINCH_SPEED,
+            max_length: WINCH_MAX_LENGTH,
+        }),
+        Keycode::Down => rope.set_winch(Winch {
+            speed: WINCH_SPEED,
+            max_length: WINCH_MAX_LENGTH,
+        }),
+        Keycode::I => rope.set_integrator(next_integrator(rope.integrator())),
+        Keycode::X => rope.set_solver(match rope.solver() {
+            Solver::Springs => Solver::xpbd(),
+            Solver::Xpbd { .. } => Solver::Springs,
+        }),
+        Keycode::C => rope.set_self_collision(!rope.self_collision()),
+        Keycode::T => rope.set_breaking_limit(match rope.breaking_limit() {
+            Some(_) => None,
+            None => Some(BreakingLimit {
+                tension: 2.0,
+                duration: 0.1,
+            }),
+        }),
+        Keycode::H => {
+            if rope.is_pinned(0) {
+                rope.unpin(0);
+            } else {
+                rope.pin(0, cursor);
+            }
         }
-    }
-
-    fn dot(self, other: Vec2) -> f64 {
-        self.x * other.x + self.y * other.y
-    }
-
-    fn project_onto(self, other: Vec2) -> Vec2 {
-        other.normalized() * (self.dot(other) / other.length())
-    }
-
-    fn rotated90(self, cw: bool) -> Vec2 {
-        let invert_x_for_ccw = (self.x < 0.0) ^ (self.y < 0.0);
-        if invert_x_for_ccw ^ cw {
-            Vec2 {
-                x: self.y * -1.0,
-                y: self.x,
+        Keycode::P => {
+            let tail = rope.segments().len() - 1;
+            if rope.is_pinned(tail) {
+                rope.unpin(tail);
+            } else {
+                rope.pin(tail, rope.segments()[tail].pos());
             }
-        } else {
-            Vec2 {
-                x: self.y,
-                y: self.x * -1.0,
+        }
+        _ => {
+            if let Some(params) = preset_for_key(keycode) {
+                rope.set_params(params).unwrap();
             }
         }
     }
 }
 
-impl Add for Vec2 {
-    type Output = Self;
+/// Joins the tail of `rope_id` to the nearest segment of another rope, or pins it to the
+/// world with a joint when no other rope is close enough.
+fn join_tail(world: &mut World, rope_id: RopeId) {
+    const REACH: f64 = 100.0;
+    let tail = match world.rope(rope_id) {
+        Some(rope) => rope.segments().len() - 1,
+        None => return,
+    };
+    let tail_end = JointEnd::Segment {
+        rope: rope_id,
+        index: tail,
+    };
+    let tail_pos = world.end_position(tail_end).unwrap();
+    let nearest = world
+        .ropes()
+        .filter(|(id, _)| *id != rope_id)
+        .flat_map(|(id, rope)| {
+            rope.segments()
+                .iter()
+                .enumerate()
+                .map(move |(index, s)| ((s.pos() - tail_pos).length(), id, index))
+        })
+        .filter(|(distance, _, _)| *distance < REACH)
+        .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let other_end = match nearest {
+        Some((_, rope, index)) => JointEnd::Segment { rope, index },
+        None => JointEnd::Anchor(tail_pos),
+    };
+    world.add_joint(Joint::revolute(other_end, tail_end));
+}
 
-    fn add(mut self, other: Vec2) -> Vec2 {
-        self += other;
-        self
-    }
+/// Hangs a body from the tail of `rope_id` by a point just above its top edge.
+fn attach_body(world: &mut World, rope_id: RopeId, body: Body, half_height: f64) {
+    let tail = match world.rope(rope_id) {
+        Some(rope) => rope.segments().len() - 1,
+        None => return,
+    };
+    let tail_end = JointEnd::Segment {
+        rope: rope_id,
+        index: tail,
+    };
+    let tail_pos = world.end_position(tail_end).unwrap();
+    let local = Vec2::new(0.0, -(half_height + Rope::DRAW_WIDTH / 2.0));
+    let mut body = body;
+    body.set_pos(tail_pos - local);
+    let body_end = JointEnd::Body {
+        body: world.add_body(body),
+        local,
+    };
+    world.add_joint(Joint::revolute(tail_end, body_end));
 }
 
-impl AddAssign for Vec2 {
-    fn add_assign(&mut self, other: Vec2) {
-        self.x += other.x;
-        self.y += other.y;
+/// Keys toggling the fields of a world built from `scene`.
+fn scene_field_keys(scene: &Scene, world: &World) -> Result<Vec<(Keycode, FieldId)>, String> {
+    let mut field_keys = Vec::new();
+    for (field, (id, _)) in scene.fields.iter().zip(world.environment().fields()) {
+        if let Some(name) = &field.key {
+            let keycode = Keycode::from_name(name)
+                .ok_or_else(|| format!("line {}: unknown key name: {}", field.key_line, name))?;
+            field_keys.push((keycode, id));
+        }
     }
+    Ok(field_keys)
 }
 
-impl Sub for Vec2 {
-    type Output = Self;
-
-    fn sub(mut self, other: Vec2) -> Vec2 {
-        self -= other;
-        self
-    }
+/// Everything the simulation needs to play out the same way from the same inputs.
+struct Demo {
+    world: World,
+    field_keys: Vec<(Keycode, FieldId)>,
+    active_rope: Option<RopeId>,
+    cursor: Vec2,
+    slice_start: Option<Vec2>,
 }
 
-impl SubAssign for Vec2 {
-    fn sub_assign(&mut self, other: Vec2) {
-        self.x -= other.x;
-        self.y -= other.y;
+impl Demo {
+    fn new(window_size: Vec2) -> Demo {
+        let mut env = Environment::new();
+        let field_keys = add_demo_fields(&mut env, window_size);
+        add_demo_colliders(&mut env, window_size);
+        let mut world = World::new(env);
+        world.set_rope_collision(true);
+        world.set_sleeping(Some(SleepThreshold {
+            energy: 1e-3,
+            duration: 1.0,
+        }));
+        let cursor = window_size / 2.0;
+        let active_rope = world.add_rope(spawn_rope(cursor));
+        Demo {
+            world,
+            field_keys,
+            active_rope: Some(active_rope),
+            cursor,
+            slice_start: None,
+        }
     }
-}
 
-impl Div<f64> for Vec2 {
-    type Output = Self;
+    /// The mouse starts out holding the first rope that asks to follow the cursor, if any.
+    fn from_scene(scene: &Scene, window_size: Vec2) -> Result<Demo, String> {
+        let mut world = scene.build();
+        let field_keys = scene_field_keys(scene, &world)?;
+        let active_rope = scene
+            .ropes
+            .iter()
+            .zip(world.ropes())
+            .find(|(rope, _)| rope.follow_cursor)
+            .map(|(_, (id, _))| id);
+        let cursor = match active_rope.and_then(|id| world.rope_mut(id)) {
+            Some(rope) => {
+                let head = rope.segments()[0].pos();
+                rope.set_cursor(head);
+                head
+            }
+            None => window_size / 2.0,
+        };
+        Ok(Demo {
+            world,
+            field_keys,
+            active_rope,
+            cursor,
+            slice_start: None,
+        })
+    }
 
-    fn div(self, rhs: f64) -> Vec2 {
-        Vec2 {
-            x: self.x / rhs,
-            y: self.y / rhs,
+    /// Takes the new settings in place while the ropes are laid out the same, otherwise
+    /// starts over from the new scene.
+    fn reload(&mut self, previous: &Scene, scene: &Scene, window_size: Vec2) -> Result<(), String> {
+        if scene.same_topology(previous) {
+            self.field_keys = scene_field_keys(scene, &self.world)?;
+            scene.update(previous, &mut self.world);
+        } else {
+            *self = Demo::from_scene(scene, window_size)?;
         }
+        Ok(())
     }
-}
-
-impl Mul<f64> for Vec2 {
-    type Output = Self;
 
-    fn mul(self, rhs: f64) -> Vec2 {
-        Vec2 {
-            x: self.x * rhs,
-            y: self.y * rhs,
+    fn apply(&mut self, input: &Input) {
+        let world = &mut self.world;
+        match input {
+            Input::Cursor(pos) => self.cursor = *pos,
+            Input::KeyDown(name) => {
+                let keycode = match Keycode::from_name(name) {
+                    Some(keycode) => keycode,
+                    None => return,
+                };
+                if let Some(active_rope) = self.active_rope {
+                    if keycode == Keycode::J {
+                        join_tail(world, active_rope);
+                    } else if keycode == Keycode::B {
+                        let ball = Body::circle(Vec2::ZERO, 30.0, 20.0);
+                        attach_body(world, active_rope, ball, 30.0);
+                    } else if keycode == Keycode::K {
+                        let crate_box = Body::rectangle(Vec2::ZERO, Vec2::new(25.0, 25.0), 10.0);
+                        attach_body(world, active_rope, crate_box, 25.0);
+                    } else if let Some(rope) = world.rope_mut(active_rope) {
+                        handle_rope_key(rope, keycode, self.cursor);
+                    }
+                }
+                for &(_, id) in self.field_keys.iter().filter(|(key, _)| *key == keycode) {
+                    world.environment_mut().toggle_field(id);
+                }
+            }
+            Input::KeyUp(name) => {
+                if name == "Up" || name == "Down" {
+                    if let Some(rope) = self.active_rope.and_then(|id| world.rope_mut(id)) {
+                        rope.stop_winch();
+                    }
+                }
+            }
+            Input::ButtonDown(Button::Left) => {
+                if let Some(rope) = self.active_rope.and_then(|id| world.rope_mut(id)) {
+                    rope.release_cursor();
+                }
+                self.active_rope = Some(world.add_rope(spawn_rope(self.cursor)));
+            }
+            Input::ButtonDown(Button::Right) => self.slice_start = Some(self.cursor),
+            Input::ButtonUp(Button::Right) => {
+                if let Some(start) = self.slice_start.take() {
+                    world.slice(start, self.cursor);
+                }
+            }
+            Input::ButtonUp(Button::Left) => {}
+        }
+        if let Some(rope) = self.active_rope.and_then(|id| world.rope_mut(id)) {
+            rope.set_cursor(self.cursor);
+            rope.move_pin(0, self.cursor);
         }
     }
-}
 
-struct RopeSegment {
-    pos: Vec2,
-    speed: Vec2,
-    force: Vec2,
+    fn draw(&self, canvas: &mut Canvas<Window>, alpha: f64) {
+        self.world.draw(canvas, alpha);
+        if let Some(start) = self.slice_start {
+            canvas
+                .thick_line(
+                    start.x as i16,
+                    start.y as i16,
+                    self.cursor.x as i16,
+                    self.cursor.y as i16,
+                    2,
+                    Color::RED,
+                )
+                .unwrap();
+        }
+    }
 }
 
-impl RopeSegment {
-    const MASS: f64 = 0.5;
-    const STIFFNESS: f64 = 0.5;
-    const DAMPING: f64 = 0.015;
-    const LENGTH: f64 = 20.0;
-
-    const STATIC_FRICTION: f64 = 0.0016;
-    const KINETIC_FRICTION: f64 = 0.0008;
-
-    fn apply_force_to_linked_segment(&self, linked: &mut RopeSegment) {
-        let pull = (self.pos - linked.pos).length_sub(Self::LENGTH);
-        if pull.length() < ZERO_THRESHOLD {
-            return;
+/// Turns a live SDL event into demo input, or `None` if the demo doesn't react to it.
+fn to_input(event: Event, input_aggregator: &mut InputAggregator) -> Option<Input> {
+    match event {
+        Event::KeyDown {
+            keycode: Some(keycode),
+            ..
+        } => Some(Input::KeyDown(keycode.name())),
+        Event::KeyUp {
+            keycode: Some(keycode),
+            ..
+        } => Some(Input::KeyUp(keycode.name())),
+        Event::MouseButtonDown { mouse_btn, .. } => button(mouse_btn).map(Input::ButtonDown),
+        Event::MouseButtonUp { mouse_btn, .. } => button(mouse_btn).map(Input::ButtonUp),
+        other_event => {
+            let cursor = input_aggregator.cursor();
+            input_aggregator.process_event(other_event);
+            Some(Input::Cursor(input_aggregator.cursor()))
+                .filter(|_| input_aggregator.cursor() != cursor)
         }
-        let spring_speed = (self.speed - linked.speed).project_onto(pull);
-        let spring_damping = spring_speed * Self::DAMPING;
-        let pull_dampened = pull + spring_damping;
-        linked.pull(pull_dampened * Self::STIFFNESS);
     }
+}
 
-    fn pull(&mut self, force: Vec2) {
-        self.force += force;
+fn button(mouse_btn: MouseButton) -> Option<Button> {
+    match mouse_btn {
+        MouseButton::Left => Some(Button::Left),
+        MouseButton::Right => Some(Button::Right),
+        _ => None,
     }
+}
 
-    fn tick(&mut self) {
-        let mut friction_applied = false;
-        if self.speed.length() < ZERO_THRESHOLD {
-            self.speed = Vec2::ZERO;
-            self.force = self.force.length_sub(Self::STATIC_FRICTION);
-            friction_applied = true;
-        }
-        self.force = self.force.length_clamped(FORCE_CAP);
-        self.speed += self.force / Self::MASS;
-        if !friction_applied {
-            self.speed = self.speed.length_sub(Self::KINETIC_FRICTION / Self::MASS);
-        }
-        self.speed = self.speed.length_clamped(SPEED_CAP);
-        self.pos += self.speed;
-        self.force = Vec2::ZERO;
-    }
+enum Mode {
+    Live,
+    Record(String),
+    Replay(Recording),
 }
 
-struct Rope {
-    cursor: Vec2,
-    segments: Vec<RopeSegment>,
+/// Reloads the scene file whenever it is saved.
+struct SceneWatcher {
+    path: String,
+    modified: Option<SystemTime>,
+    last_check: Instant,
 }
 
-impl Rope {
-    const DRAW_WIDTH: f64 = 10.0;
-
-    fn new(n: usize, pos: Vec2) -> Rope {
-        let mut segments = Vec::new();
-        for _ in 0..n {
-            segments.push(RopeSegment {
-                pos,
-                speed: Vec2::ZERO,
-                force: Vec2::ZERO,
-            });
-        }
-        Rope {
-            cursor: pos,
-            segments,
-        }
-    }
+impl SceneWatcher {
+    const INTERVAL: Duration = Duration::from_millis(250);
 
-    fn tick(&mut self) {
-        let diff = self.cursor - self.segments[0].pos;
-        if diff.length() > ZERO_THRESHOLD {
-            self.segments[0].force += diff * 0.0005;
-        }
-        for i in 0..self.segments.len() {
-            if i != 0 {
-                let (left, right) = self.segments.split_at_mut(i);
-                right[0].apply_force_to_linked_segment(&mut left[i - 1]);
-            }
-            if i != self.segments.len() - 1 {
-                let (left, right) = self.segments.split_at_mut(i + 1);
-                left[i].apply_force_to_linked_segment(&mut right[0]);
-            }
-        }
-        for segment in &mut self.segments {
-            segment.tick()
+    fn new(path: String) -> SceneWatcher {
+        SceneWatcher {
+            modified: modified_time(&path),
+            path,
+            last_check: Instant::now(),
         }
     }
 
-    fn draw(&self, canvas: &mut Canvas<Window>) {
-        canvas
-            .filled_circle(
-                self.cursor.x.round() as i16,
-                self.cursor.y.round() as i16,
-                (Self::DRAW_WIDTH / 1.5) as i16,
-                Color::BLACK,
-            )
-            .unwrap();
-
-        for s in &self.segments {
-            canvas
-                .filled_circle(
-                    s.pos.x as i16,
-                    s.pos.y as i16,
-                    (Self::DRAW_WIDTH / 2.0) as i16,
-                    Color::WHITE,
-                )
-                .unwrap();
+    /// The scene as it is on disk if the file changed since the last poll.
+    fn poll(&mut self) -> Option<Result<Scene, String>> {
+        if self.last_check.elapsed() < SceneWatcher::INTERVAL {
+            return None;
         }
-        for segments in self.segments[..].windows(2) {
-            if let [s1, s2] = segments {
-                if (s2.pos - s1.pos).length() < ZERO_THRESHOLD {
-                    continue;
-                }
-                let s1norm =
-                    (s2.pos - s1.pos).normalized().rotated90(true) * (Self::DRAW_WIDTH / 2.0);
-                let s2norm =
-                    (s1.pos - s2.pos).normalized().rotated90(false) * (Self::DRAW_WIDTH / 2.0);
-                let s1a = s1.pos + s1norm;
-                let s1b = s1.pos - s1norm;
-                let s2a = s2.pos + s2norm;
-                let s2b = s2.pos - s2norm;
-                canvas
-                    .filled_polygon(
-                        &[s1a.x as i16, s1b.x as i16, s2b.x as i16, s2a.x as i16],
-                        &[s1a.y as i16, s1b.y as i16, s2b.y as i16, s2a.y as i16],
-                        Color::WHITE,
-                    )
-                    .unwrap();
-            }
+        self.last_check = Instant::now();
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return None;
         }
+        self.modified = modified;
+        Some(Scene::load(&self.path).map_err(|err| format!("{}: {}", self.path, err)))
     }
 }
 
-struct InputAggregator {
-    window_size: Vec2,
-    active_finger: Option<i64>,
-    cursor: Vec2,
+fn modified_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
 }
 
-impl InputAggregator {
-    const MOUSE_SENSITIVITY: f64 = 0.5;
+/// How long a snapped link stays marked, in seconds.
+const BREAK_MARK_DURATION: f64 = 0.5;
 
-    pub fn new(window_size: Vec2) -> InputAggregator {
-        InputAggregator {
-            window_size,
-            active_finger: None,
-            cursor: window_size / 2.0,
-        }
+/// Marks each snapped link with a red ring that fades out.
+fn draw_breaks(canvas: &mut Canvas<Window>, breaks: &[(Vec2, f64)]) {
+    for &(pos, left) in breaks {
+        let alpha = (255.0 * left / BREAK_MARK_DURATION) as u8;
+        let radius = 8.0 + 16.0 * (1.0 - left / BREAK_MARK_DURATION);
+        canvas
+            .aa_circle(
+                pos.x as i16,
+                pos.y as i16,
+                radius as i16,
+                Color::RGBA(255, 0, 0, alpha),
+            )
+            .unwrap();
     }
+}
 
-    pub fn process_event(&mut self, event: Event) {
-        if let Some(active_finger_id) = self.active_finger {
-            match event {
-                Event::FingerMotion {
-                    finger_id, x, y, ..
-                } if finger_id == active_finger_id => {
-                    self.set_cursor_from_finger_position(x, y);
-                }
-                Event::FingerUp {
-                    finger_id, x, y, ..
-                } if finger_id == active_finger_id => {
-                    self.active_finger = None;
-                    self.set_cursor_from_finger_position(x, y);
-                }
-                _ => {}
+const USAGE: &str = "usage: sdl [--scene PATH] [--record PATH | --replay PATH]";
+
+fn parse_args() -> Result<(Mode, Option<(String, Scene)>), String> {
+    let mut mode = Mode::Live;
+    let mut scene = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let path = args.next().ok_or(USAGE)?;
+        match arg.as_str() {
+            "--scene" => {
+                let loaded = Scene::load(&path).map_err(|err| format!("{}: {}", path, err))?;
+                scene = Some((path, loaded));
             }
-        } else {
-            match event {
-                Event::FingerDown {
-                    finger_id, x, y, ..
-                } => {
-                    self.active_finger = Some(finger_id);
-                    self.set_cursor_from_finger_position(x, y);
-                }
-                Event::MouseMotion { xrel, yrel, .. } => {
-                    self.cursor += Vec2 {
-                        x: xrel.into(),
-                        y: yrel.into(),
-                    } * Self::MOUSE_SENSITIVITY;
-                }
-                _ => {}
+            "--record" if matches!(mode, Mode::Live) => mode = Mode::Record(path),
+            "--replay" if matches!(mode, Mode::Live) => {
+                let file = File::open(&path).map_err(|err| format!("{}: {}", path, err))?;
+                let recording = Recording::read(BufReader::new(file))
+                    .map_err(|err| format!("{}: {}", path, err))?;
+                mode = Mode::Replay(recording);
             }
+            _ => return Err(USAGE.to_string()),
         }
     }
-
-    fn set_cursor_from_finger_position(&mut self, x: f32, y: f32) {
-        // Finger positions are always in range of 0..1
-        self.cursor = Vec2 {
-            x: x as f64 * self.window_size.x,
-            y: y as f64 * self.window_size.y,
-        };
-    }
+    Ok((mode, scene))
 }
 
 fn main() {
+    let (mode, mut scene) = parse_args().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(2);
+    });
+
     sdl2::hint::set("SDL_HINT_TOUCH_MOUSE_EVENTS", "0");
     sdl2::hint::set("SDL_HINT_MOUSE_TOUCH_EVENTS", "0");
 
@@ -349,7 +535,34 @@ fn main() {
             y: y.into(),
         })
     };
-    let mut rope = Rope::new(40, input_aggregator.cursor);
+    // A replay has to start from the scene it was recorded in.
+    let window_size = match &mode {
+        Mode::Replay(recording) => recording.window_size,
+        _ => input_aggregator.window_size(),
+    };
+    let dt = match &mode {
+        Mode::Replay(recording) => recording.dt,
+        _ => NOMINAL_DT,
+    };
+    // A replay of a scene needs the same `--scene` it was recorded with.
+    let mut demo = match &scene {
+        Some((path, scene)) => Demo::from_scene(scene, window_size).unwrap_or_else(|err| {
+            eprintln!("{}: {}", path, err);
+            process::exit(2);
+        }),
+        None => Demo::new(window_size),
+    };
+    // Edits to the scene file aren't part of a recording, so only live sessions follow them.
+    let mut watcher = match (&mode, &scene) {
+        (Mode::Live, Some((path, _))) => Some(SceneWatcher::new(path.clone())),
+        _ => None,
+    };
+    let mut recording = Recording::new(dt, window_size);
+    let mut tick = 0;
+    let mut timestep = FixedTimestep::new(dt, 60);
+    let mut last_frame = Instant::now();
+    // Where links snapped, with the seconds left to mark them.
+    let mut breaks: Vec<(Vec2, f64)> = Vec::new();
 
     'running: loop {
         for event in event_pump.poll_iter() {
@@ -359,19 +572,95 @@ fn main() {
                     keycode: Some(Keycode::Escape),
                     ..
                 } => break 'running,
-                other_event => input_aggregator.process_event(other_event),
+                _ if matches!(mode, Mode::Replay(_)) => {}
+                event => {
+                    if let Some(input) = to_input(event, &mut input_aggregator) {
+                        demo.apply(&input);
+                        recording.push(tick, input);
+                    }
+                }
+            }
+        }
+        if let (Some(watcher), Some((_, current))) = (&mut watcher, &mut scene) {
+            match watcher.poll() {
+                Some(Ok(reloaded)) => match demo.reload(current, &reloaded, window_size) {
+                    Ok(()) => {
+                        println!("reloaded {}", watcher.path);
+                        *current = reloaded;
+                    }
+                    Err(err) => eprintln!("{}: {}", watcher.path, err),
+                },
+                Some(Err(err)) => eprintln!("{}", err),
+                None => {}
+            }
+        }
+        let now = Instant::now();
+        let elapsed = now.duration_since(last_frame).as_secs_f64();
+        let steps = timestep.advance(elapsed);
+        last_frame = now;
+        for _ in 0..steps {
+            if let Mode::Replay(replay) = &mode {
+                // Inputs handled in the recording's last frame come after its last step,
+                // but still count towards its checksum.
+                for input in replay.inputs_at(tick) {
+                    demo.apply(input);
+                }
+                if tick == replay.ticks {
+                    let checksum = demo.world.checksum();
+                    if checksum == replay.checksum {
+                        println!("replay matches, checksum {:016x}", checksum);
+                    } else {
+                        println!(
+                            "replay diverged: checksum {:016x}, recorded {:016x}",
+                            checksum, replay.checksum
+                        );
+                    }
+                    break 'running;
+                }
             }
-            rope.cursor = input_aggregator.cursor;
+            demo.world.step(timestep.dt());
+            tick += 1;
         }
-        for _ in 0..15 {
-            rope.tick();
+        for (_, left) in &mut breaks {
+            *left -= elapsed;
+        }
+        breaks.retain(|(_, left)| *left > 0.0);
+        let broken: Vec<_> = demo
+            .world
+            .drain_events()
+            .filter_map(|event| match event {
+                rope_sim::Event::LinkBroken { rope, link, .. } => Some((rope, link)),
+                _ => None,
+            })
+            .collect();
+        for (rope, link) in broken {
+            let end = demo
+                .world
+                .rope(rope)
+                .and_then(|rope| rope.segments().get(link));
+            if let Some(end) = end {
+                breaks.push((end.pos(), BREAK_MARK_DURATION));
+            }
         }
 
         canvas.set_draw_color(Color::GREY);
         canvas.clear();
-        rope.draw(&mut canvas);
+        demo.draw(&mut canvas, timestep.alpha());
+        draw_breaks(&mut canvas, &breaks);
         canvas.present();
+    }
 
-        ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+    if let Mode::Record(path) = mode {
+        recording.ticks = tick;
+        recording.checksum = demo.world.checksum();
+        let written = File::create(&path).and_then(|file| {
+            let mut out = BufWriter::new(file);
+            recording.write(&mut out)?;
+            out.flush()
+        });
+        match written {
+            Ok(()) => println!("recorded {} ticks to {}", tick, path),
+            Err(err) => eprintln!("{}: {}", path, err),
+        }
     }
 }
@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
+
+pub const ZERO_THRESHOLD: f64 = 0.00001;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vec2 {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2 { x: 0.0, y: 0.0 };
+
+    pub fn new(x: f64, y: f64) -> Vec2 {
+        Vec2 { x, y }
+    }
+
+    pub fn length(self) -> f64 {
+        (self.x.powf(2.0) + self.y.powf(2.0)).sqrt()
+    }
+
+    pub fn normalized(self) -> Vec2 {
+        self / self.length()
+    }
+
+    pub fn length_sub(self, amnt: f64) -> Vec2 {
+        if self.length() < amnt || self.length() < ZERO_THRESHOLD {
+            Vec2::ZERO
+        } else {
+            self - self.normalized() * amnt
+        }
+    }
+
+    pub fn length_clamped(self, amnt: f64) -> Vec2 {
+        if self.length() < amnt {
+            self
+        } else {
+            self.normalized() * amnt
+        }
+    }
+
+    pub fn lerp(self, other: Vec2, t: f64) -> Vec2 {
+        self + (other - self) * t
+    }
+
+    pub fn dot(self, other: Vec2) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Z component of the 3D cross product.
+    pub fn cross(self, other: Vec2) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub fn project_onto(self, other: Vec2) -> Vec2 {
+        other.normalized() * (self.dot(other) / other.length())
+    }
+
+    /// Rotates counter-clockwise by `angle` radians (clockwise on screen, where y points down).
+    pub fn rotated(self, angle: f64) -> Vec2 {
+        let (sin, cos) = angle.sin_cos();
+        Vec2 {
+            x: self.x * cos - self.y * sin,
+            y: self.x * sin + self.y * cos,
+        }
+    }
+
+    /// Rotated a quarter turn counter-clockwise (clockwise on screen, where y points down).
+    pub fn perpendicular(self) -> Vec2 {
+        Vec2 {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    pub fn rotated90(self, cw: bool) -> Vec2 {
+        let invert_x_for_ccw = (self.x < 0.0) ^ (self.y < 0.0);
+        if invert_x_for_ccw ^ cw {
+            Vec2 {
+                x: -self.y,
+                y: self.x,
+            }
+        } else {
+            Vec2 {
+                x: self.y,
+                y: -self.x,
+            }
+        }
+    }
+}
+
+pub fn closest_point_on_segment(p: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+    let ab = b - a;
+    let length_squared = ab.dot(ab);
+    if length_squared < ZERO_THRESHOLD {
+        return a;
+    }
+    let t = ((p - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    a + ab * t
+}
+
+/// Closest points between segments `p1..q1` and `p2..q2`, as parameters along each segment.
+pub fn closest_points_between_segments(p1: Vec2, q1: Vec2, p2: Vec2, q2: Vec2) -> (f64, f64) {
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+    let a = d1.dot(d1);
+    let e = d2.dot(d2);
+    let f = d2.dot(r);
+    if a < ZERO_THRESHOLD && e < ZERO_THRESHOLD {
+        return (0.0, 0.0);
+    }
+    if a < ZERO_THRESHOLD {
+        return (0.0, (f / e).clamp(0.0, 1.0));
+    }
+    let c = d1.dot(r);
+    if e < ZERO_THRESHOLD {
+        return ((-c / a).clamp(0.0, 1.0), 0.0);
+    }
+    let b = d1.dot(d2);
+    let denom = a * e - b * b;
+    let mut s = if denom > ZERO_THRESHOLD {
+        ((b * f - c * e) / denom).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let mut t = (b * s + f) / e;
+    if t < 0.0 {
+        t = 0.0;
+        s = (-c / a).clamp(0.0, 1.0);
+    } else if t > 1.0 {
+        t = 1.0;
+        s = ((b - c) / a).clamp(0.0, 1.0);
+    }
+    (s, t)
+}
+
+impl Add for Vec2 {
+    type Output = Self;
+
+    fn add(mut self, other: Vec2) -> Vec2 {
+        self += other;
+        self
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, other: Vec2) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Self;
+
+    fn sub(mut self, other: Vec2) -> Vec2 {
+        self -= other;
+        self
+    }
+}
+
+impl SubAssign for Vec2 {
+    fn sub_assign(&mut self, other: Vec2) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl Div<f64> for Vec2 {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Vec2 {
+        Vec2 {
+            x: self.x / rhs,
+            y: self.y / rhs,
+        }
+    }
+}
+
+impl Mul<f64> for Vec2 {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Vec2 {
+        Vec2 {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RopeParams {
+    pub mass: f64,
+    pub stiffness: f64,
+    pub damping: f64,
+    pub length: f64,
+    pub static_friction: f64,
+    pub kinetic_friction: f64,
+    pub speed_cap: f64,
+    pub force_cap: f64,
+}
+
+impl RopeParams {
+    pub const PRESETS: [&'static str; 4] = ["default", "chain", "rubber band", "thread"];
+
+    pub fn chain() -> RopeParams {
+        RopeParams {
+            mass: 1.0,
+            stiffness: 0.8,
+            damping: 0.03,
+            length: 20.0,
+            static_friction: 0.004,
+            kinetic_friction: 0.002,
+            ..RopeParams::default()
+        }
+    }
+
+    pub fn rubber_band() -> RopeParams {
+        RopeParams {
+            mass: 0.3,
+            stiffness: 0.05,
+            damping: 0.005,
+            length: 15.0,
+            static_friction: 0.0008,
+            kinetic_friction: 0.0002,
+            ..RopeParams::default()
+        }
+    }
+
+    pub fn thread() -> RopeParams {
+        RopeParams {
+            mass: 0.1,
+            stiffness: 0.1,
+            damping: 0.01,
+            length: 10.0,
+            static_friction: 0.0004,
+            kinetic_friction: 0.0002,
+            ..RopeParams::default()
+        }
+    }
+
+    pub fn preset(name: &str) -> Option<RopeParams> {
+        match name {
+            "default" => Some(RopeParams::default()),
+            "chain" => Some(RopeParams::chain()),
+            "rubber band" | "rubber_band" => Some(RopeParams::rubber_band()),
+            "thread" => Some(RopeParams::thread()),
+            _ => None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ParamsError> {
+        let positive = [
+            ("mass", self.mass),
+            ("stiffness", self.stiffness),
+            ("speed_cap", self.speed_cap),
+            ("force_cap", self.force_cap),
+        ];
+        let non_negative = [
+            ("damping", self.damping),
+            ("length", self.length),
+            ("static_friction", self.static_friction),
+            ("kinetic_friction", self.kinetic_friction),
+        ];
+        for &(field, value) in &positive {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(ParamsError::NotPositive { field, value });
+            }
+        }
+        for &(field, value) in &non_negative {
+            if !value.is_finite() || value < 0.0 {
+                return Err(ParamsError::Negative { field, value });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for RopeParams {
+    fn default() -> RopeParams {
+        RopeParams {
+            mass: 0.5,
+            stiffness: 0.5,
+            damping: 0.015,
+            length: 20.0,
+            static_friction: 0.0016,
+            kinetic_friction: 0.0008,
+            speed_cap: 100.0,
+            force_cap: 50.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamsError {
+    NotPositive {
+        field: &'static str,
+        value: f64,
+    },
+    Negative {
+        field: &'static str,
+        value: f64,
+    },
+    /// A rope was asked for without any segments.
+    NoSegments,
+}
+
+impl fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParamsError::NotPositive { field, value } => {
+                write!(f, "{} must be a positive number, got {}", field, value)
+            }
+            ParamsError::Negative { field, value } => {
+                write!(f, "{} must be a non-negative number, got {}", field, value)
+            }
+            ParamsError::NoSegments => write!(f, "a rope needs at least one segment"),
+        }
+    }
+}
+
+impl Error for ParamsError {}
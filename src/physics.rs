@@ -0,0 +1,915 @@
+use crate::broadphase::SpatialHash;
+use crate::collision::CapsuleContact;
+use crate::environment::{ColliderId, Environment};
+use crate::event::RopeEvent;
+use crate::integrator::{Integrator, SemiImplicitEuler};
+use crate::layout::RopeShape;
+use crate::math::{Vec2, ZERO_THRESHOLD};
+use crate::params::{ParamsError, RopeParams};
+use crate::solver::{self, Solver};
+use serde::{Deserialize, Serialize};
+use std::mem;
+use std::sync::Arc;
+
+/// Step size the parameters are tuned for. Other step sizes are scaled relative to it.
+pub const NOMINAL_DT: f64 = 1.0 / 900.0;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct RopeSegment {
+    pub(crate) pos: Vec2,
+    pub(crate) prev_pos: Vec2,
+    pub(crate) speed: Vec2,
+    pub(crate) force: Vec2,
+}
+
+impl RopeSegment {
+    pub fn pos(&self) -> Vec2 {
+        self.pos
+    }
+
+    pub fn speed(&self) -> Vec2 {
+        self.speed
+    }
+
+    /// Position blended between the last two ticks, for rendering between steps.
+    pub fn interpolated_pos(&self, alpha: f64) -> Vec2 {
+        self.prev_pos.lerp(self.pos, alpha)
+    }
+
+    pub fn set_pos(&mut self, pos: Vec2) {
+        self.pos = pos;
+    }
+
+    pub fn set_speed(&mut self, speed: Vec2) {
+        self.speed = speed;
+    }
+
+    /// Force accumulated through `pull` since the last tick.
+    pub fn force(&self) -> Vec2 {
+        self.force
+    }
+
+    fn force_on_linked_segment(
+        &self,
+        linked: &RopeSegment,
+        length: f64,
+        params: &RopeParams,
+    ) -> Vec2 {
+        let pull = (self.pos - linked.pos).length_sub(length);
+        if pull.length() < ZERO_THRESHOLD {
+            return Vec2::ZERO;
+        }
+        let spring_speed = (self.speed - linked.speed).project_onto(pull);
+        let spring_damping = spring_speed * params.damping;
+        let pull_dampened = pull + spring_damping;
+        pull_dampened * params.stiffness
+    }
+
+    pub fn pull(&mut self, force: Vec2) {
+        self.force += force;
+    }
+
+    pub(crate) fn tick(&mut self, params: &RopeParams, dt: f64) {
+        let scale = dt / NOMINAL_DT;
+        let mut friction_applied = false;
+        if self.speed.length() < ZERO_THRESHOLD {
+            self.speed = Vec2::ZERO;
+            self.force = self.force.length_sub(params.static_friction);
+            friction_applied = true;
+        }
+        self.force = self.force.length_clamped(params.force_cap);
+        self.speed += self.force / params.mass * scale;
+        if !friction_applied {
+            self.speed = self
+                .speed
+                .length_sub(params.kinetic_friction / params.mass * scale);
+        }
+        self.speed = self.speed.length_clamped(params.speed_cap);
+        self.pos += self.speed * scale;
+        self.force = Vec2::ZERO;
+    }
+}
+
+/// Holds a segment exactly at `target`. Moving the target drags the segment along with it.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pin {
+    pub index: usize,
+    pub target: Vec2,
+}
+
+/// Reels the rope in or out at its head, the end a crane or grappling hook would hold.
+///
+/// `speed` is in pixels of rest length per `NOMINAL_DT`, negative to reel in. Paying out
+/// stops once the rope's rest length reaches `max_length`, reeling in once a single link
+/// of zero length is left.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Winch {
+    pub speed: f64,
+    pub max_length: f64,
+}
+
+/// Makes every link of a rope snap once its tension stays above `tension` for
+/// `duration` seconds. The limit is shared by all links of the rope, while each link
+/// keeps its own timer.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreakingLimit {
+    pub tension: f64,
+    pub duration: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Rope {
+    cursor: Option<Vec2>,
+    params: RopeParams,
+    #[serde(with = "crate::integrator::by_name_serde")]
+    integrator: Arc<dyn Integrator>,
+    solver: Solver,
+    self_collision: bool,
+    segments: Vec<RopeSegment>,
+    pins: Vec<Pin>,
+    head_length: f64,
+    winch: Option<Winch>,
+    breaking_limit: Option<BreakingLimit>,
+    tensions: Vec<f64>,
+    overstressed_for: Vec<f64>,
+    contacts: Vec<(usize, ColliderId)>,
+    at_rest: bool,
+    events: Vec<RopeEvent>,
+    pub(crate) asleep: bool,
+    pub(crate) calm_for: f64,
+    pub(crate) disturbed: bool,
+}
+
+impl Rope {
+    pub const DRAW_WIDTH: f64 = 10.0;
+
+    pub fn new(n: usize, pos: Vec2, params: RopeParams) -> Result<Rope, ParamsError> {
+        params.validate()?;
+        if n == 0 {
+            return Err(ParamsError::NoSegments);
+        }
+        let mut segments = Vec::new();
+        for _ in 0..n {
+            segments.push(RopeSegment {
+                pos,
+                prev_pos: pos,
+                speed: Vec2::ZERO,
+                force: Vec2::ZERO,
+            });
+        }
+        Ok(Rope {
+            cursor: None,
+            params,
+            integrator: Arc::new(SemiImplicitEuler),
+            solver: Solver::default(),
+            self_collision: false,
+            segments,
+            pins: Vec::new(),
+            head_length: params.length,
+            winch: None,
+            breaking_limit: None,
+            tensions: Vec::new(),
+            overstressed_for: Vec::new(),
+            contacts: Vec::new(),
+            at_rest: true,
+            events: Vec::new(),
+            asleep: false,
+            calm_for: 0.0,
+            disturbed: true,
+        })
+    }
+
+    /// A still rope with a segment at each of `points`.
+    pub fn through(points: &[Vec2], params: RopeParams) -> Result<Rope, ParamsError> {
+        let mut rope = Rope::new(points.len(), Vec2::ZERO, params)?;
+        for (segment, &pos) in rope.segments.iter_mut().zip(points) {
+            segment.pos = pos;
+            segment.prev_pos = pos;
+        }
+        Ok(rope)
+    }
+
+    /// A still rope laid out along `shape` with every link at rest length, as many
+    /// segments as fit. The tail ends up less than one link short of the end of the shape.
+    pub fn along(shape: &RopeShape, params: RopeParams) -> Result<Rope, ParamsError> {
+        params.validate()?;
+        if params.length <= 0.0 {
+            return Err(ParamsError::NotPositive {
+                field: "length",
+                value: params.length,
+            });
+        }
+        Rope::through(&shape.points_at_spacing(params.length), params)
+    }
+
+    pub fn along_line(from: Vec2, to: Vec2, params: RopeParams) -> Result<Rope, ParamsError> {
+        Rope::along(&RopeShape::Line { from, to }, params)
+    }
+
+    pub fn along_polyline(points: &[Vec2], params: RopeParams) -> Result<Rope, ParamsError> {
+        let points = points.to_vec();
+        Rope::along(&RopeShape::Polyline { points }, params)
+    }
+
+    /// Angles are in radians, clockwise on screen.
+    pub fn along_arc(
+        center: Vec2,
+        radius: f64,
+        from: f64,
+        to: f64,
+        params: RopeParams,
+    ) -> Result<Rope, ParamsError> {
+        let shape = RopeShape::Arc {
+            center,
+            radius,
+            from,
+            to,
+        };
+        Rope::along(&shape, params)
+    }
+
+    /// Winds outwards from `inner_radius` at `angle` radians.
+    pub fn along_spiral(
+        center: Vec2,
+        inner_radius: f64,
+        outer_radius: f64,
+        turns: f64,
+        angle: f64,
+        params: RopeParams,
+    ) -> Result<Rope, ParamsError> {
+        let shape = RopeShape::Spiral {
+            center,
+            inner_radius,
+            outer_radius,
+            turns,
+            angle,
+        };
+        Rope::along(&shape, params)
+    }
+
+    /// Follows the cubic Bézier curve from `from` to `to`.
+    pub fn along_bezier(
+        from: Vec2,
+        controls: [Vec2; 2],
+        to: Vec2,
+        params: RopeParams,
+    ) -> Result<Rope, ParamsError> {
+        Rope::along(&RopeShape::Bezier { from, controls, to }, params)
+    }
+
+    pub fn cursor(&self) -> Option<Vec2> {
+        self.cursor
+    }
+
+    /// Starts gently pulling the first segment towards `cursor`.
+    pub fn set_cursor(&mut self, cursor: Vec2) {
+        if self.cursor != Some(cursor) {
+            self.cursor = Some(cursor);
+            self.disturbed = true;
+        }
+    }
+
+    pub fn release_cursor(&mut self) {
+        self.cursor = None;
+        self.disturbed = true;
+    }
+
+    pub fn params(&self) -> &RopeParams {
+        &self.params
+    }
+
+    pub fn set_params(&mut self, params: RopeParams) -> Result<(), ParamsError> {
+        params.validate()?;
+        self.params = params;
+        self.disturbed = true;
+        Ok(())
+    }
+
+    pub fn integrator(&self) -> &dyn Integrator {
+        self.integrator.as_ref()
+    }
+
+    pub fn set_integrator(&mut self, integrator: Box<dyn Integrator>) {
+        self.integrator = integrator.into();
+        self.disturbed = true;
+    }
+
+    pub fn solver(&self) -> Solver {
+        self.solver
+    }
+
+    pub fn set_solver(&mut self, solver: Solver) {
+        self.solver = solver;
+        self.disturbed = true;
+    }
+
+    pub fn self_collision(&self) -> bool {
+        self.self_collision
+    }
+
+    /// Makes links that aren't neighbours push each other apart, treating each link as
+    /// a capsule `DRAW_WIDTH` wide.
+    pub fn set_self_collision(&mut self, enabled: bool) {
+        self.self_collision = enabled;
+    }
+
+    pub fn segments(&self) -> &[RopeSegment] {
+        &self.segments
+    }
+
+    pub(crate) fn segments_mut(&mut self) -> &mut [RopeSegment] {
+        &mut self.segments
+    }
+
+    pub fn pull_segment(&mut self, index: usize, force: Vec2) {
+        self.segments[index].pull(force);
+        self.disturbed = true;
+    }
+
+    pub fn pins(&self) -> &[Pin] {
+        &self.pins
+    }
+
+    pub fn is_pinned(&self, index: usize) -> bool {
+        self.pins.iter().any(|pin| pin.index == index)
+    }
+
+    /// Fixes segment `index` at `target`, replacing any pin it already had.
+    pub fn pin(&mut self, index: usize, target: Vec2) {
+        assert!(index < self.segments.len(), "pin index out of range");
+        self.unpin(index);
+        self.pins.push(Pin { index, target });
+        let segment = &mut self.segments[index];
+        segment.pos = target;
+        segment.prev_pos = target;
+        segment.speed = Vec2::ZERO;
+        self.disturbed = true;
+    }
+
+    /// Moves the anchor of a pinned segment. The segment follows on the next tick,
+    /// picking up the anchor's speed so that linked segments are dragged along smoothly.
+    pub fn move_pin(&mut self, index: usize, target: Vec2) {
+        if let Some(pin) = self.pins.iter_mut().find(|pin| pin.index == index) {
+            if pin.target != target {
+                pin.target = target;
+                self.disturbed = true;
+            }
+        }
+    }
+
+    pub fn unpin(&mut self, index: usize) {
+        self.pins.retain(|pin| pin.index != index);
+        self.disturbed = true;
+    }
+
+    /// Cuts link `link`, between segments `link` and `link + 1`. This rope keeps the
+    /// segments up to `link` and the rest are returned as a new rope with the same
+    /// settings, their speeds and pins intact. The cursor stays with this rope.
+    pub fn split_off(&mut self, link: usize) -> Rope {
+        assert!(link + 1 < self.segments.len(), "link index out of range");
+        let segments = self.segments.split_off(link + 1);
+        self.disturbed = true;
+        let (kept, moved): (Vec<Pin>, Vec<Pin>) =
+            self.pins.iter().partition(|pin| pin.index <= link);
+        self.pins = kept;
+        let (kept, moved_contacts): (Vec<_>, Vec<_>) = self
+            .contacts
+            .iter()
+            .partition(|(segment, _)| *segment <= link);
+        self.contacts = kept;
+        // Break timers go with their links, and the cut link's is dropped.
+        let moved_timers = if self.overstressed_for.is_empty() {
+            Vec::new()
+        } else {
+            let moved = self.overstressed_for.split_off(link + 1);
+            self.overstressed_for.truncate(link);
+            moved
+        };
+        Rope {
+            cursor: None,
+            params: self.params,
+            integrator: self.integrator.clone(),
+            solver: self.solver,
+            self_collision: self.self_collision,
+            segments,
+            pins: moved
+                .into_iter()
+                .map(|pin| Pin {
+                    index: pin.index - link - 1,
+                    target: pin.target,
+                })
+                .collect(),
+            head_length: self.params.length,
+            winch: None,
+            breaking_limit: self.breaking_limit,
+            tensions: Vec::new(),
+            overstressed_for: moved_timers,
+            contacts: moved_contacts
+                .into_iter()
+                .map(|(segment, collider)| (segment - link - 1, collider))
+                .collect(),
+            at_rest: self.at_rest,
+            events: Vec::new(),
+            asleep: false,
+            calm_for: 0.0,
+            disturbed: true,
+        }
+    }
+
+    /// Links the head of `other` to this rope's tail, keeping this rope's settings. The
+    /// links taken over from `other` are at full rest length.
+    pub fn join(&mut self, other: Rope) {
+        let offset = self.segments.len();
+        self.disturbed = true;
+        if !self.overstressed_for.is_empty() || !other.overstressed_for.is_empty() {
+            let mut timers = other.overstressed_for;
+            timers.resize(other.segments.len().saturating_sub(1), 0.0);
+            // The new link between the two ropes starts out relaxed.
+            self.overstressed_for.resize(offset, 0.0);
+            self.overstressed_for.extend(timers);
+        }
+        self.segments.extend(other.segments);
+        self.pins.extend(other.pins.into_iter().map(|pin| Pin {
+            index: pin.index + offset,
+            target: pin.target,
+        }));
+        self.contacts.extend(
+            other
+                .contacts
+                .into_iter()
+                .map(|(segment, collider)| (segment + offset, collider)),
+        );
+        self.at_rest &= other.at_rest;
+    }
+
+    /// Swaps the head and tail. The cursor keeps pulling the first segment, which is now
+    /// the old tail. A partly reeled head link is reset to full rest length.
+    pub fn reverse(&mut self) {
+        self.head_length = self.params.length;
+        let last = self.segments.len() - 1;
+        self.segments.reverse();
+        for pin in &mut self.pins {
+            pin.index = last - pin.index;
+        }
+        for (segment, _) in &mut self.contacts {
+            *segment = last - *segment;
+        }
+        self.contacts.sort_unstable();
+        self.overstressed_for.reverse();
+    }
+
+    /// Rest length of link `link`. Only the head link differs from `params().length`,
+    /// while it is partly reeled.
+    pub fn link_length(&self, link: usize) -> f64 {
+        if link == 0 {
+            self.head_length
+        } else {
+            self.params.length
+        }
+    }
+
+    /// Total rest length of all links.
+    pub fn rest_length(&self) -> f64 {
+        let links = self.segments.len().saturating_sub(1);
+        (0..links).map(|link| self.link_length(link)).sum()
+    }
+
+    pub fn winch(&self) -> Option<Winch> {
+        self.winch
+    }
+
+    /// Starts reeling the rope at the winch's speed on every tick.
+    pub fn set_winch(&mut self, winch: Winch) {
+        self.winch = Some(winch);
+        self.disturbed = true;
+    }
+
+    pub fn stop_winch(&mut self) {
+        self.winch = None;
+    }
+
+    /// Lengthens the rope by `amount` at its head, or shortens it when negative, without
+    /// letting its rest length exceed `max_length`.
+    ///
+    /// The head link's rest length changes smoothly. Once it grows past a full link a new
+    /// segment is inserted right after the head, and once it shrinks to nothing the
+    /// segment after the head is removed, so the indices of every segment but the head
+    /// shift by one. Ropes with zero-length links can't be reeled, so nothing happens.
+    pub fn reel(&mut self, amount: f64, max_length: f64) {
+        if self.segments.len() < 2 || self.params.length <= 0.0 {
+            return;
+        }
+        self.disturbed = true;
+        let room = (max_length - self.rest_length()).max(0.0);
+        self.head_length += amount.min(room);
+        while self.head_length > self.params.length {
+            self.head_length -= self.params.length;
+            let (head, next) = (self.segments[0], self.segments[1]);
+            let offset = next.pos - head.pos;
+            let t = if offset.length() < ZERO_THRESHOLD {
+                0.0
+            } else {
+                (self.head_length / offset.length()).min(1.0)
+            };
+            let pos = head.pos.lerp(next.pos, t);
+            self.segments.insert(
+                1,
+                RopeSegment {
+                    pos,
+                    prev_pos: head.prev_pos.lerp(next.prev_pos, t),
+                    speed: head.speed.lerp(next.speed, t),
+                    force: Vec2::ZERO,
+                },
+            );
+            self.shift_pins(1);
+        }
+        while self.head_length < 0.0 {
+            if self.segments.len() == 2 {
+                self.head_length = 0.0;
+                break;
+            }
+            self.head_length += self.params.length;
+            self.segments.remove(1);
+            self.pins.retain(|pin| pin.index != 1);
+            self.contacts.retain(|(segment, _)| *segment != 1);
+            self.shift_pins(-1);
+        }
+    }
+
+    /// Moves pins, contacts and link break timers past the head along by `by` segments,
+    /// after a segment was inserted at index 1 or removed from it.
+    fn shift_pins(&mut self, by: isize) {
+        if !self.overstressed_for.is_empty() {
+            if by > 0 {
+                self.overstressed_for.insert(1, 0.0);
+            } else if self.overstressed_for.len() > 1 {
+                self.overstressed_for.remove(1);
+            }
+        }
+        for pin in &mut self.pins {
+            if pin.index >= 1 {
+                pin.index = (pin.index as isize + by) as usize;
+            }
+        }
+        for (segment, _) in &mut self.contacts {
+            if *segment >= 1 {
+                *segment = (*segment as isize + by) as usize;
+            }
+        }
+    }
+
+    pub fn breaking_limit(&self) -> Option<BreakingLimit> {
+        self.breaking_limit
+    }
+
+    pub fn set_breaking_limit(&mut self, limit: Option<BreakingLimit>) {
+        self.breaking_limit = limit;
+        self.overstressed_for.clear();
+    }
+
+    /// Force each link pulled its segments together with on the last tick, zero for
+    /// slack links.
+    pub fn tensions(&self) -> &[f64] {
+        &self.tensions
+    }
+
+    /// Events from the ticks since the last call, oldest first. Events queue up until
+    /// taken, so a rope ticked on its own rather than in a `World` should take them
+    /// regularly.
+    pub fn take_events(&mut self) -> Vec<RopeEvent> {
+        mem::take(&mut self.events)
+    }
+
+    /// Whether the rope was put to sleep by its `World`, see `World::set_sleeping`.
+    pub fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+
+    /// Whether every segment stood still on the last tick.
+    pub fn is_at_rest(&self) -> bool {
+        self.at_rest
+    }
+
+    pub fn kinetic_energy(&self) -> f64 {
+        self.segments
+            .iter()
+            .map(|s| 0.5 * self.params.mass * s.speed.dot(s.speed))
+            .sum()
+    }
+
+    /// Energy stored in stretched links.
+    pub fn potential_energy(&self) -> f64 {
+        self.segments
+            .windows(2)
+            .enumerate()
+            .map(|(link, pair)| {
+                let stretch = (pair[1].pos - pair[0].pos).length() - self.link_length(link);
+                0.5 * self.params.stiffness * stretch.max(0.0).powi(2)
+            })
+            .sum()
+    }
+
+    pub fn energy(&self) -> f64 {
+        self.kinetic_energy() + self.potential_energy()
+    }
+
+    /// Advances the rope by `dt` seconds. Does nothing unless `dt` is positive, since
+    /// pinned segments get their speed by dividing by it.
+    pub fn tick(&mut self, dt: f64, env: &Environment) {
+        if dt.is_nan() || dt <= 0.0 {
+            return;
+        }
+        let scale = dt / NOMINAL_DT;
+        if let Some(winch) = self.winch {
+            self.reel(winch.speed * scale, winch.max_length);
+        }
+        let mut segments = mem::take(&mut self.segments);
+        for segment in &mut segments {
+            segment.prev_pos = segment.pos;
+        }
+        for pin in &self.pins {
+            let segment = &mut segments[pin.index];
+            segment.speed = (pin.target - segment.pos) / scale;
+        }
+        self.integrator
+            .step(&mut segments, &self.params, dt, &|state, forces| {
+                self.accumulate_forces(state, forces, env)
+            });
+        for pin in &self.pins {
+            let segment = &mut segments[pin.index];
+            segment.pos = pin.target;
+            segment.speed = (pin.target - segment.prev_pos) / scale;
+        }
+        if let Solver::Xpbd {
+            compliance,
+            iterations,
+        } = self.solver
+        {
+            let inverse_masses = self.inverse_masses(segments.len());
+            let lengths: Vec<f64> = (0..segments.len().saturating_sub(1))
+                .map(|link| self.link_length(link))
+                .collect();
+            let lambdas = solver::project_links(
+                &mut segments,
+                &inverse_masses,
+                &lengths,
+                compliance,
+                iterations,
+                scale,
+            );
+            for segment in &mut segments {
+                segment.speed = (segment.pos - segment.prev_pos) / scale;
+            }
+            self.tensions = lambdas
+                .iter()
+                .map(|lambda| -lambda / (scale * scale))
+                .collect();
+        } else {
+            self.tensions = segments
+                .windows(2)
+                .enumerate()
+                .map(|(link, pair)| {
+                    let stretch = (pair[1].pos - pair[0].pos).length() - self.link_length(link);
+                    self.params.stiffness * stretch.max(0.0)
+                })
+                .collect();
+        }
+        self.check_breaking_limit(dt);
+        if self.self_collision {
+            self.collide_with_self(&mut segments);
+        }
+        let contacts = self.collide_with_environment(&mut segments, env);
+        self.update_contacts(contacts);
+        let at_rest = segments.iter().all(|s| s.speed.length() < ZERO_THRESHOLD);
+        if at_rest && !self.at_rest {
+            self.events.push(RopeEvent::CameToRest);
+        }
+        self.at_rest = at_rest;
+        self.segments = segments;
+    }
+
+    fn check_breaking_limit(&mut self, dt: f64) {
+        let limit = match self.breaking_limit {
+            Some(limit) => limit,
+            None => return,
+        };
+        if self.overstressed_for.len() != self.tensions.len() {
+            self.overstressed_for = vec![0.0; self.tensions.len()];
+        }
+        for (link, &tension) in self.tensions.iter().enumerate().rev() {
+            let timer = &mut self.overstressed_for[link];
+            if tension <= limit.tension {
+                *timer = 0.0;
+                continue;
+            }
+            if *timer == 0.0 {
+                self.events
+                    .push(RopeEvent::TensionExceeded { link, tension });
+            }
+            *timer += dt;
+            let broken = RopeEvent::LinkBroken { link };
+            if *timer >= limit.duration && !self.events.contains(&broken) {
+                self.events.push(broken);
+            }
+        }
+    }
+
+    /// Finds state the methods above never produce but a hand-edited snapshot might,
+    /// like a pin past the last segment.
+    pub(crate) fn check(&self) -> Result<(), String> {
+        self.params.validate().map_err(|err| err.to_string())?;
+        let n = self.segments.len();
+        if n == 0 {
+            return Err("a rope needs at least one segment".to_string());
+        }
+        if let Some(pin) = self.pins.iter().find(|pin| pin.index >= n) {
+            return Err(format!(
+                "pin {} is past the last segment of a {}-segment rope",
+                pin.index, n
+            ));
+        }
+        if let Some((segment, _)) = self.contacts.iter().find(|(segment, _)| *segment >= n) {
+            return Err(format!(
+                "contact at segment {} is past the last segment of a {}-segment rope",
+                segment, n
+            ));
+        }
+        if !self.overstressed_for.is_empty() && self.overstressed_for.len() != n - 1 {
+            return Err(format!(
+                "{} break timers for a {}-segment rope",
+                self.overstressed_for.len(),
+                n
+            ));
+        }
+        if !(self.head_length.is_finite() && self.head_length >= 0.0) {
+            return Err(format!(
+                "head link length must be a non-negative number, got {}",
+                self.head_length
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn inverse_mass(&self, index: usize) -> f64 {
+        if self.is_pinned(index) {
+            0.0
+        } else {
+            1.0 / self.params.mass
+        }
+    }
+
+    pub(crate) fn inverse_masses(&self, n: usize) -> Vec<f64> {
+        let mut inverse_masses = vec![1.0 / self.params.mass; n];
+        for pin in &self.pins {
+            inverse_masses[pin.index] = 0.0;
+        }
+        inverse_masses
+    }
+
+    fn collide_with_self(&self, segments: &mut [RopeSegment]) {
+        let radius = Self::DRAW_WIDTH / 2.0;
+        let inverse_masses = self.inverse_masses(segments.len());
+        let mut grid = SpatialHash::new(self.params.length + Self::DRAW_WIDTH);
+        for (i, link) in segments.windows(2).enumerate() {
+            let (a, b) = (link[0].pos, link[1].pos);
+            let min = Vec2::new(a.x.min(b.x) - radius, a.y.min(b.y) - radius);
+            let max = Vec2::new(a.x.max(b.x) + radius, a.y.max(b.y) + radius);
+            grid.insert(i, min, max);
+        }
+        for (i, j) in grid.pairs() {
+            if j - i < 2 {
+                continue;
+            }
+            let ends = [i, i + 1, j, j + 1];
+            let contact = match CapsuleContact::between(ends.map(|k| segments[k].pos), radius) {
+                Some(contact) => contact,
+                None => continue,
+            };
+            let (pos_deltas, speed_deltas) = contact.resolve(
+                ends.map(|k| segments[k].speed),
+                ends.map(|k| inverse_masses[k]),
+            );
+            for (k, &index) in ends.iter().enumerate() {
+                segments[index].pos += pos_deltas[k];
+                segments[index].speed += speed_deltas[k];
+            }
+        }
+    }
+
+    /// Resolves contacts with the environment's colliders and returns the touching
+    /// `(segment, collider)` pairs in order.
+    fn collide_with_environment(
+        &self,
+        segments: &mut [RopeSegment],
+        env: &Environment,
+    ) -> Vec<(usize, ColliderId)> {
+        let radius = Self::DRAW_WIDTH / 2.0;
+        let mut contacts = Vec::new();
+        for (i, segment) in segments.iter_mut().enumerate() {
+            if self.is_pinned(i) {
+                continue;
+            }
+            for (id, collider) in env.colliders() {
+                if collider.resolve(&mut segment.pos, &mut segment.speed, radius) {
+                    contacts.push((i, id));
+                }
+            }
+        }
+        contacts.sort_unstable();
+        contacts
+    }
+
+    fn update_contacts(&mut self, contacts: Vec<(usize, ColliderId)>) {
+        for &(segment, collider) in &self.contacts {
+            if contacts.binary_search(&(segment, collider)).is_err() {
+                self.events
+                    .push(RopeEvent::ContactEnded { segment, collider });
+            }
+        }
+        for &(segment, collider) in &contacts {
+            if self.contacts.binary_search(&(segment, collider)).is_err() {
+                self.events
+                    .push(RopeEvent::ContactBegan { segment, collider });
+            }
+        }
+        self.contacts = contacts;
+    }
+
+    fn accumulate_forces(&self, state: &[RopeSegment], forces: &mut [Vec2], env: &Environment) {
+        for (force, s) in forces.iter_mut().zip(state) {
+            *force = s.force + env.force_at(s.pos, s.speed, self.params.mass);
+        }
+        if let Some(cursor) = self.cursor {
+            let diff = cursor - state[0].pos;
+            if diff.length() > ZERO_THRESHOLD {
+                forces[0] += diff * 0.0005;
+            }
+        }
+        self.accumulate_link_forces(state, forces);
+        for pin in &self.pins {
+            forces[pin.index] = Vec2::ZERO;
+        }
+    }
+
+    fn accumulate_link_forces(&self, state: &[RopeSegment], forces: &mut [Vec2]) {
+        if self.solver != Solver::Springs {
+            return;
+        }
+        for i in 0..state.len() {
+            if i != 0 {
+                forces[i - 1] += state[i].force_on_linked_segment(
+                    &state[i - 1],
+                    self.link_length(i - 1),
+                    &self.params,
+                );
+            }
+            if i != state.len() - 1 {
+                forces[i + 1] += state[i].force_on_linked_segment(
+                    &state[i + 1],
+                    self.link_length(i),
+                    &self.params,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn break_timers_follow_their_links() {
+        let mut rope = Rope::new(5, Vec2::ZERO, RopeParams::default()).unwrap();
+        rope.overstressed_for = vec![1.0, 2.0, 3.0, 4.0];
+        let mut tail = rope.split_off(1);
+        assert_eq!(rope.overstressed_for, [1.0]);
+        assert_eq!(tail.overstressed_for, [3.0, 4.0]);
+        tail.reverse();
+        assert_eq!(tail.overstressed_for, [4.0, 3.0]);
+        rope.join(tail);
+        assert_eq!(rope.overstressed_for, [1.0, 0.0, 4.0, 3.0]);
+    }
+
+    #[test]
+    fn ticking_by_zero_leaves_pinned_ropes_alone() {
+        let mut rope = Rope::new(3, Vec2::ZERO, RopeParams::default()).unwrap();
+        rope.pin(0, Vec2::new(10.0, 0.0));
+        rope.tick(0.0, &Environment::new());
+        assert!(rope.segments().iter().all(|s| s.speed == Vec2::ZERO));
+        assert_eq!(rope.segments()[0].pos(), Vec2::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn reeling_zero_length_links_does_nothing() {
+        let params = RopeParams {
+            length: 0.0,
+            ..RopeParams::default()
+        };
+        let mut rope = Rope::new(3, Vec2::ZERO, params).unwrap();
+        rope.reel(1.0, 100.0);
+        rope.reel(-1.0, 100.0);
+        assert_eq!(rope.segments().len(), 3);
+        assert_eq!(rope.rest_length(), 0.0);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::math::Vec2;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+const HEADER: &str = "rope-recording 1";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Button {
+    Left,
+    Right,
+}
+
+/// A single piece of user input, applied right before a simulation tick.
+///
+/// Finger and mouse motion are both recorded as the cursor position they lead to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Input {
+    Cursor(Vec2),
+    KeyDown(String),
+    KeyUp(String),
+    ButtonDown(Button),
+    ButtonUp(Button),
+}
+
+/// Input of a whole session, tagged with the tick it was applied before.
+///
+/// Floats are stored as their exact bits, so replaying the inputs with the same `dt` and
+/// window size reproduces the session bit for bit. `checksum` is the `World::checksum`
+/// after the last tick and tells whether a replay diverged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    pub dt: f64,
+    pub window_size: Vec2,
+    pub inputs: Vec<(u64, Input)>,
+    pub ticks: u64,
+    pub checksum: u64,
+}
+
+impl Recording {
+    pub fn new(dt: f64, window_size: Vec2) -> Recording {
+        Recording {
+            dt,
+            window_size,
+            inputs: Vec::new(),
+            ticks: 0,
+            checksum: 0,
+        }
+    }
+
+    pub fn push(&mut self, tick: u64, input: Input) {
+        self.inputs.push((tick, input));
+    }
+
+    /// Inputs to apply before tick `tick`.
+    pub fn inputs_at(&self, tick: u64) -> impl Iterator<Item = &Input> {
+        let start = self.inputs.partition_point(|(t, _)| *t < tick);
+        self.inputs[start..]
+            .iter()
+            .take_while(move |(t, _)| *t == tick)
+            .map(|(_, input)| input)
+    }
+
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "{}", HEADER)?;
+        writeln!(out, "dt {:x}", self.dt.to_bits())?;
+        writeln!(
+            out,
+            "window {:x} {:x}",
+            self.window_size.x.to_bits(),
+            self.window_size.y.to_bits()
+        )?;
+        for (tick, input) in &self.inputs {
+            match input {
+                Input::Cursor(pos) => {
+                    writeln!(
+                        out,
+                        "{} cursor {:x} {:x}",
+                        tick,
+                        pos.x.to_bits(),
+                        pos.y.to_bits()
+                    )
+                }
+                Input::KeyDown(key) => writeln!(out, "{} key-down {}", tick, key),
+                Input::KeyUp(key) => writeln!(out, "{} key-up {}", tick, key),
+                Input::ButtonDown(button) => {
+                    writeln!(out, "{} button-down {}", tick, button_name(*button))
+                }
+                Input::ButtonUp(button) => {
+                    writeln!(out, "{} button-up {}", tick, button_name(*button))
+                }
+            }?;
+        }
+        writeln!(out, "end {} {:016x}", self.ticks, self.checksum)
+    }
+
+    pub fn read(input: impl BufRead) -> Result<Recording, RecordingError> {
+        let mut lines = input.lines().enumerate().map(|(i, line)| (i + 1, line));
+        let mut next = |expected: &str| match lines.next() {
+            Some((n, Ok(line))) => Ok((n, line)),
+            Some((_, Err(err))) => Err(RecordingError::Io(err)),
+            None => Err(RecordingError::Truncated {
+                expected: expected.to_string(),
+            }),
+        };
+        let (n, line) = next("header")?;
+        if line != HEADER {
+            return Err(RecordingError::parse(n, "not a rope recording"));
+        }
+        let (n, line) = next("dt")?;
+        let dt = match line.split(' ').collect::<Vec<_>>()[..] {
+            ["dt", bits] => parse_f64(n, bits)?,
+            _ => return Err(RecordingError::parse(n, "expected dt")),
+        };
+        let (n, line) = next("window size")?;
+        let window_size = match line.split(' ').collect::<Vec<_>>()[..] {
+            ["window", x, y] => Vec2::new(parse_f64(n, x)?, parse_f64(n, y)?),
+            _ => return Err(RecordingError::parse(n, "expected window size")),
+        };
+        let mut recording = Recording::new(dt, window_size);
+        loop {
+            let (n, line) = next("end")?;
+            let words: Vec<&str> = line.splitn(3, ' ').collect();
+            if let ["end", ticks, checksum] = words[..] {
+                recording.ticks = ticks
+                    .parse()
+                    .map_err(|_| RecordingError::parse(n, "bad tick count"))?;
+                recording.checksum = u64::from_str_radix(checksum, 16)
+                    .map_err(|_| RecordingError::parse(n, "bad checksum"))?;
+                return Ok(recording);
+            }
+            let (tick, kind, args) = match words[..] {
+                [tick, kind, args] => (tick, kind, args),
+                _ => return Err(RecordingError::parse(n, "expected tick, kind and value")),
+            };
+            let tick: u64 = tick
+                .parse()
+                .map_err(|_| RecordingError::parse(n, "bad tick"))?;
+            if recording
+                .inputs
+                .last()
+                .is_some_and(|(last, _)| *last > tick)
+            {
+                return Err(RecordingError::parse(n, "ticks out of order"));
+            }
+            let input = match kind {
+                "cursor" => match args.split(' ').collect::<Vec<_>>()[..] {
+                    [x, y] => Input::Cursor(Vec2::new(parse_f64(n, x)?, parse_f64(n, y)?)),
+                    _ => return Err(RecordingError::parse(n, "expected cursor x and y")),
+                },
+                "key-down" => Input::KeyDown(args.to_string()),
+                "key-up" => Input::KeyUp(args.to_string()),
+                "button-down" => Input::ButtonDown(parse_button(n, args)?),
+                "button-up" => Input::ButtonUp(parse_button(n, args)?),
+                other => {
+                    return Err(RecordingError::parse(
+                        n,
+                        &format!("unknown input kind {}", other),
+                    ))
+                }
+            };
+            recording.push(tick, input);
+        }
+    }
+}
+
+fn button_name(button: Button) -> &'static str {
+    match button {
+        Button::Left => "left",
+        Button::Right => "right",
+    }
+}
+
+fn parse_button(line: usize, name: &str) -> Result<Button, RecordingError> {
+    match name {
+        "left" => Ok(Button::Left),
+        "right" => Ok(Button::Right),
+        _ => Err(RecordingError::parse(line, "unknown button")),
+    }
+}
+
+fn parse_f64(line: usize, bits: &str) -> Result<f64, RecordingError> {
+    u64::from_str_radix(bits, 16)
+        .map(f64::from_bits)
+        .map_err(|_| RecordingError::parse(line, "bad number"))
+}
+
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    Truncated { expected: String },
+}
+
+impl RecordingError {
+    fn parse(line: usize, message: &str) -> RecordingError {
+        RecordingError::Parse {
+            line,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordingError::Io(err) => write!(f, "{}", err),
+            RecordingError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            RecordingError::Truncated { expected } => {
+                write!(f, "recording ends early, expected {}", expected)
+            }
+        }
+    }
+}
+
+impl Error for RecordingError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Recording {
+        let mut recording = Recording::new(1.0 / 900.0, Vec2::new(1280.0, 720.0));
+        recording.push(0, Input::Cursor(Vec2::new(0.1, -3.25)));
+        recording.push(0, Input::KeyDown("Left Shift".to_string()));
+        recording.push(4, Input::ButtonDown(Button::Left));
+        recording.push(9, Input::ButtonUp(Button::Right));
+        recording.push(9, Input::KeyUp("Left Shift".to_string()));
+        recording.ticks = 12;
+        recording.checksum = 0x0123_4567_89ab_cdef;
+        recording
+    }
+
+    fn written(recording: &Recording) -> String {
+        let mut out = Vec::new();
+        recording.write(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let recording = session();
+        let read = Recording::read(written(&recording).as_bytes()).unwrap();
+        assert_eq!(read, recording);
+        assert_eq!(read.inputs_at(9).count(), 2);
+    }
+
+    #[test]
+    fn ticks_out_of_order_are_rejected() {
+        let text = written(&session()).replace("4 button-down", "10 button-down");
+        match Recording::read(text.as_bytes()) {
+            Err(RecordingError::Parse { line, .. }) => assert_eq!(line, 7),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn truncated_recordings_are_rejected() {
+        let text = written(&session());
+        let text = &text[..text.find("end").unwrap()];
+        assert!(matches!(
+            Recording::read(text.as_bytes()),
+            Err(RecordingError::Truncated { .. })
+        ));
+        assert!(matches!(
+            Recording::read("".as_bytes()),
+            Err(RecordingError::Truncated { .. })
+        ));
+    }
+
+    #[test]
+    fn other_files_are_rejected() {
+        assert!(matches!(
+            Recording::read("tick,segment,x,y\n".as_bytes()),
+            Err(RecordingError::Parse { line: 1, .. })
+        ));
+    }
+}
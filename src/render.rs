@@ -0,0 +1,152 @@
+use crate::body::{Body, BodyShape};
+use crate::collision::Shape;
+use crate::environment::Environment;
+use crate::math::{Vec2, ZERO_THRESHOLD};
+use crate::physics::Rope;
+use crate::world::World;
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::pixels::Color;
+use sdl2::render::Canvas;
+use sdl2::video::Window;
+
+impl Rope {
+    pub fn draw(&self, canvas: &mut Canvas<Window>, alpha: f64) {
+        if let Some(cursor) = self.cursor() {
+            canvas
+                .filled_circle(
+                    cursor.x.round() as i16,
+                    cursor.y.round() as i16,
+                    (Self::DRAW_WIDTH / 1.5) as i16,
+                    Color::BLACK,
+                )
+                .unwrap();
+        }
+
+        for s in self.segments() {
+            canvas
+                .filled_circle(
+                    s.interpolated_pos(alpha).x as i16,
+                    s.interpolated_pos(alpha).y as i16,
+                    (Self::DRAW_WIDTH / 2.0) as i16,
+                    Color::WHITE,
+                )
+                .unwrap();
+        }
+        for segments in self.segments().windows(2) {
+            if let [s1, s2] = segments {
+                let (p1, p2) = (s1.interpolated_pos(alpha), s2.interpolated_pos(alpha));
+                if (p2 - p1).length() < ZERO_THRESHOLD {
+                    continue;
+                }
+                let s1norm = (p2 - p1).normalized().rotated90(true) * (Self::DRAW_WIDTH / 2.0);
+                let s2norm = (p1 - p2).normalized().rotated90(false) * (Self::DRAW_WIDTH / 2.0);
+                let s1a = p1 + s1norm;
+                let s1b = p1 - s1norm;
+                let s2a = p2 + s2norm;
+                let s2b = p2 - s2norm;
+                canvas
+                    .filled_polygon(
+                        &[s1a.x as i16, s1b.x as i16, s2b.x as i16, s2a.x as i16],
+                        &[s1a.y as i16, s1b.y as i16, s2b.y as i16, s2a.y as i16],
+                        Color::WHITE,
+                    )
+                    .unwrap();
+            }
+        }
+    }
+}
+
+impl Environment {
+    const COLLIDER_COLOR: Color = Color::RGB(64, 64, 64);
+
+    pub fn draw(&self, canvas: &mut Canvas<Window>) {
+        for (_, collider) in self.colliders() {
+            match &collider.shape {
+                Shape::Circle { center, radius } => canvas
+                    .filled_circle(
+                        center.x as i16,
+                        center.y as i16,
+                        *radius as i16,
+                        Self::COLLIDER_COLOR,
+                    )
+                    .unwrap(),
+                Shape::Segment { a, b } => canvas
+                    .thick_line(
+                        a.x as i16,
+                        a.y as i16,
+                        b.x as i16,
+                        b.y as i16,
+                        2,
+                        Self::COLLIDER_COLOR,
+                    )
+                    .unwrap(),
+                shape => {
+                    let outline = shape.outline();
+                    let xs: Vec<i16> = outline.iter().map(|p| p.x as i16).collect();
+                    let ys: Vec<i16> = outline.iter().map(|p| p.y as i16).collect();
+                    canvas
+                        .filled_polygon(&xs, &ys, Self::COLLIDER_COLOR)
+                        .unwrap();
+                }
+            }
+        }
+    }
+}
+
+impl Body {
+    const COLOR: Color = Color::RGB(160, 82, 45);
+
+    pub fn draw(&self, canvas: &mut Canvas<Window>, alpha: f64) {
+        let (pos, angle) = self.interpolated(alpha);
+        match self.shape() {
+            BodyShape::Circle { radius } => {
+                canvas
+                    .filled_circle(pos.x as i16, pos.y as i16, radius as i16, Self::COLOR)
+                    .unwrap();
+                let rim = pos + Vec2::new(radius, 0.0).rotated(angle);
+                canvas
+                    .line(
+                        pos.x as i16,
+                        pos.y as i16,
+                        rim.x as i16,
+                        rim.y as i16,
+                        Color::BLACK,
+                    )
+                    .unwrap();
+            }
+            BodyShape::Box { half_extents } => {
+                let outline = Shape::Box {
+                    center: pos,
+                    half_extents,
+                    angle,
+                }
+                .outline();
+                let xs: Vec<i16> = outline.iter().map(|p| p.x as i16).collect();
+                let ys: Vec<i16> = outline.iter().map(|p| p.y as i16).collect();
+                canvas.filled_polygon(&xs, &ys, Self::COLOR).unwrap();
+            }
+        }
+    }
+}
+
+impl World {
+    pub fn draw(&self, canvas: &mut Canvas<Window>, alpha: f64) {
+        self.environment().draw(canvas);
+        for (_, rope) in self.ropes() {
+            rope.draw(canvas, alpha);
+        }
+        for (_, body) in self.bodies() {
+            body.draw(canvas, alpha);
+        }
+        for (_, joint) in self.joints() {
+            if let (Some(a), Some(b)) = (self.end_position(joint.a), self.end_position(joint.b)) {
+                canvas
+                    .line(a.x as i16, a.y as i16, b.x as i16, b.y as i16, Color::BLACK)
+                    .unwrap();
+                canvas
+                    .filled_circle(a.x as i16, a.y as i16, 3, Color::BLACK)
+                    .unwrap();
+            }
+        }
+    }
+}
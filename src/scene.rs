@@ -0,0 +1,856 @@
+//! Scenes described in a TOML file instead of code.
+//!
+//! ```toml
+//! [world]
+//! rope_collision = true
+//! sleeping = { energy = 0.001, duration = 1.0 }
+//!
+//! [[rope]]
+//! segments = 30
+//! shape = { kind = "arc", center = [640, 200], radius = 150, from = 180, to = 360 }
+//! params = { preset = "chain", damping = 0.02 }
+//! pins = [0, 29]
+//!
+//! [[collider]]
+//! shape = { kind = "circle", center = [640, 500], radius = 60 }
+//! restitution = 0.5
+//!
+//! [[field]]
+//! force = { kind = "gravity" }
+//! key = "G"
+//! ```
+//!
+//! Points are `[x, y]` arrays and angles are in degrees, clockwise on screen. Rope
+//! shapes are `line` (`from`, `to`), `polyline` (`points`), `arc` (`center`, `radius`,
+//! `from` and `to` angles), `spiral` (`center`, `inner_radius`, `outer_radius`, `turns`
+//! and an optional start `angle`) and `bezier` (`from`, two `controls`, `to`). With
+//! `segments` given they are spread evenly along the shape, otherwise the rope is laid
+//! out with every link at rest length.
+
+use crate::collision::{Collider, Shape};
+use crate::environment::{ColliderId, Environment, FieldId};
+use crate::field::ForceField;
+use crate::integrator;
+use crate::layout::RopeShape;
+use crate::math::Vec2;
+use crate::params::RopeParams;
+use crate::physics::{BreakingLimit, Rope};
+use crate::solver::Solver;
+use crate::world::{RopeId, SleepThreshold, World};
+use serde::Deserialize;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use toml::Spanned;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub rope_collision: bool,
+    pub sleeping: Option<SleepThreshold>,
+    pub ropes: Vec<SceneRope>,
+    pub colliders: Vec<Collider>,
+    pub fields: Vec<SceneField>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneRope {
+    /// Spread evenly along the shape, or as many as fit at rest length when `None`.
+    pub segments: Option<usize>,
+    pub shape: RopeShape,
+    pub params: RopeParams,
+    /// One of `integrator::NAMES`.
+    pub integrator: String,
+    pub solver: Solver,
+    pub self_collision: bool,
+    pub breaking_limit: Option<BreakingLimit>,
+    /// Segments held where the shape puts them.
+    pub pins: Vec<usize>,
+    /// Whether the demo hands this rope to the mouse.
+    pub follow_cursor: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneField {
+    pub force: ForceField,
+    pub enabled: bool,
+    /// Name of the key toggling the field in the demo.
+    pub key: Option<String>,
+    /// Line of `key` in the scene file, for reporting a name the demo doesn't know.
+    pub key_line: usize,
+}
+
+impl SceneRope {
+    /// Panics if the rope holds something `Scene::parse` would have rejected, like an
+    /// unknown integrator or a pin past the last segment.
+    pub fn build(&self) -> Rope {
+        let rope = match self.segments {
+            Some(n) => Rope::through(&self.shape.points(n), self.params),
+            None => Rope::along(&self.shape, self.params),
+        };
+        let mut rope = rope.expect("scene rope parameters are invalid");
+        rope.set_integrator(integrator::by_name(&self.integrator).expect("unknown integrator"));
+        rope.set_solver(self.solver);
+        rope.set_self_collision(self.self_collision);
+        rope.set_breaking_limit(self.breaking_limit);
+        for &index in &self.pins {
+            let target = rope.segments()[index].pos();
+            rope.pin(index, target);
+        }
+        rope
+    }
+}
+
+impl Scene {
+    pub fn load(path: impl AsRef<Path>) -> Result<Scene, SceneError> {
+        Scene::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Scene, SceneError> {
+        let file: SceneFile = toml::from_str(text).map_err(|err| SceneError::Parse {
+            line: line_of(text, err.span()),
+            message: err.message().to_string(),
+        })?;
+        let invalid = |span: Range<usize>, message: String| SceneError::Parse {
+            line: line_of(text, Some(span)),
+            message,
+        };
+        let mut ropes = Vec::new();
+        for entry in file.rope {
+            if let Some(segments) = &entry.segments {
+                if *segments.get_ref() == 0 {
+                    return Err(invalid(
+                        segments.span(),
+                        "a rope needs at least one segment".to_string(),
+                    ));
+                }
+            }
+            let params = match entry.params {
+                Some(params) => {
+                    let span = params.span();
+                    params
+                        .into_inner()
+                        .resolve()
+                        .map_err(|message| invalid(span, message))?
+                }
+                None => RopeParams::default(),
+            };
+            let integrator = match entry.integrator {
+                Some(name) => {
+                    if integrator::by_name(name.get_ref()).is_none() {
+                        return Err(invalid(
+                            name.span(),
+                            format!(
+                                "unknown integrator {}, expected one of {}",
+                                name.get_ref(),
+                                integrator::NAMES.join(", ")
+                            ),
+                        ));
+                    }
+                    name.into_inner()
+                }
+                None => integrator::NAMES[0].to_string(),
+            };
+            let solver = match entry.solver {
+                Some(name) => match name.get_ref().as_str() {
+                    "springs" => Solver::Springs,
+                    "xpbd" => Solver::xpbd(),
+                    other => {
+                        return Err(invalid(
+                            name.span(),
+                            format!("unknown solver {}, expected springs or xpbd", other),
+                        ))
+                    }
+                },
+                None => Solver::default(),
+            };
+            let shape_span = entry.shape.span();
+            let shape = RopeShape::from(entry.shape.into_inner());
+            if shape.start().is_none() {
+                return Err(invalid(shape_span, "a polyline needs points".to_string()));
+            }
+            let segments = entry.segments.map(Spanned::into_inner);
+            let count = match segments {
+                Some(n) => n,
+                None if params.length > 0.0 => shape.points_at_spacing(params.length).len(),
+                None => {
+                    return Err(invalid(
+                        shape_span,
+                        "a rope of zero length links needs a segment count".to_string(),
+                    ))
+                }
+            };
+            let breaking_limit = match entry.breaking_limit {
+                Some(limit) => {
+                    let BreakingLimit { tension, duration } = *limit.get_ref();
+                    if !(tension.is_finite() && tension >= 0.0 && duration >= 0.0) {
+                        return Err(invalid(
+                            limit.span(),
+                            "a breaking limit needs a finite, non-negative tension and a \
+                             non-negative duration"
+                                .to_string(),
+                        ));
+                    }
+                    Some(limit.into_inner())
+                }
+                None => None,
+            };
+            let mut pins = Vec::new();
+            for pin in entry.pins {
+                if *pin.get_ref() >= count {
+                    return Err(invalid(
+                        pin.span(),
+                        format!(
+                            "pin {} is past the last segment of a {}-segment rope",
+                            pin.get_ref(),
+                            count
+                        ),
+                    ));
+                }
+                pins.push(pin.into_inner());
+            }
+            ropes.push(SceneRope {
+                segments,
+                shape,
+                params,
+                integrator,
+                solver,
+                self_collision: entry.self_collision,
+                breaking_limit,
+                pins,
+                follow_cursor: entry.follow_cursor,
+            });
+        }
+        let mut colliders = Vec::new();
+        for entry in file.collider {
+            let span = entry.shape.span();
+            let shape = entry.shape.into_inner();
+            shape.check().map_err(|message| invalid(span, message))?;
+            let mut collider = Collider::new(shape.into());
+            if let Some(restitution) = entry.restitution {
+                if !(0.0..=1.0).contains(restitution.get_ref()) {
+                    return Err(invalid(
+                        restitution.span(),
+                        "restitution must be between 0 and 1".to_string(),
+                    ));
+                }
+                collider.restitution = restitution.into_inner();
+            }
+            if let Some(friction) = entry.friction {
+                if *friction.get_ref() < 0.0 {
+                    return Err(invalid(
+                        friction.span(),
+                        "friction must not be negative".to_string(),
+                    ));
+                }
+                collider.friction = friction.into_inner();
+            }
+            colliders.push(collider);
+        }
+        let mut fields = Vec::new();
+        for entry in file.field {
+            let span = entry.force.span();
+            let force = entry.force.into_inner();
+            force.check().map_err(|message| invalid(span, message))?;
+            let key_line = entry
+                .key
+                .as_ref()
+                .map_or(0, |key| line_of(text, Some(key.span())));
+            fields.push(SceneField {
+                force: force.into(),
+                enabled: entry.enabled,
+                key: entry.key.map(Spanned::into_inner),
+                key_line,
+            });
+        }
+        let sleeping = match file.world.sleeping {
+            Some(sleeping) => {
+                let threshold = *sleeping.get_ref();
+                if threshold.energy < 0.0 || threshold.duration <= 0.0 {
+                    return Err(invalid(
+                        sleeping.span(),
+                        "sleeping needs a non-negative energy and a positive duration".to_string(),
+                    ));
+                }
+                Some(threshold)
+            }
+            None => None,
+        };
+        Ok(Scene {
+            rope_collision: file.world.rope_collision,
+            sleeping,
+            ropes,
+            colliders,
+            fields,
+        })
+    }
+
+    /// Builds a fresh world. Ropes, colliders and fields are added in the order of the
+    /// file, so the n-th of each gets the n-th id.
+    pub fn build(&self) -> World {
+        let mut env = Environment::new();
+        for field in &self.fields {
+            let id = env.add_field(field.force);
+            env.set_field_enabled(id, field.enabled);
+        }
+        for collider in &self.colliders {
+            env.add_collider(collider.clone());
+        }
+        let mut world = World::new(env);
+        world.set_rope_collision(self.rope_collision);
+        world.set_sleeping(self.sleeping);
+        for rope in &self.ropes {
+            world.add_rope(rope.build());
+        }
+        world
+    }
+
+    /// Whether a world built from `previous` can take this scene with `update` instead of
+    /// being rebuilt: the same ropes laid out the same way, and as many colliders and fields.
+    pub fn same_topology(&self, previous: &Scene) -> bool {
+        self.ropes.len() == previous.ropes.len()
+            && self.colliders.len() == previous.colliders.len()
+            && self.fields.len() == previous.fields.len()
+            && self.ropes.iter().zip(&previous.ropes).all(|(rope, old)| {
+                // Without a count the link length decides how many segments fit.
+                let same_count = rope.segments == old.segments
+                    && (rope.segments.is_some() || rope.params.length == old.params.length);
+                same_count
+                    && rope.shape == old.shape
+                    && rope.pins == old.pins
+                    && rope.follow_cursor == old.follow_cursor
+            })
+    }
+
+    /// Applies whatever changed since `previous` to a world built from it, leaving
+    /// segments where they are. Settings the file didn't change stay as they are in the
+    /// world, so a field toggled by hand isn't toggled back. Ropes and colliders that were
+    /// removed from the world are skipped.
+    ///
+    /// Panics unless the scenes have the same topology.
+    pub fn update(&self, previous: &Scene, world: &mut World) {
+        assert!(self.same_topology(previous), "scene topology changed");
+        if self.rope_collision != previous.rope_collision {
+            world.set_rope_collision(self.rope_collision);
+        }
+        if self.sleeping != previous.sleeping {
+            world.set_sleeping(self.sleeping);
+        }
+        for (i, (new, old)) in self.ropes.iter().zip(&previous.ropes).enumerate() {
+            let rope = match world.rope_mut(RopeId(i as u64)) {
+                Some(rope) => rope,
+                None => continue,
+            };
+            if new.params != old.params {
+                rope.set_params(new.params)
+                    .expect("scene rope parameters are invalid");
+            }
+            if new.integrator != old.integrator {
+                rope.set_integrator(
+                    integrator::by_name(&new.integrator).expect("unknown integrator"),
+                );
+            }
+            if new.solver != old.solver {
+                rope.set_solver(new.solver);
+            }
+            if new.self_collision != old.self_collision {
+                rope.set_self_collision(new.self_collision);
+            }
+            if new.breaking_limit != old.breaking_limit {
+                rope.set_breaking_limit(new.breaking_limit);
+            }
+        }
+        let env_changed = self.colliders != previous.colliders || self.fields != previous.fields;
+        if !env_changed {
+            return;
+        }
+        let env = world.environment_mut();
+        for (i, (new, old)) in self.colliders.iter().zip(&previous.colliders).enumerate() {
+            if new != old {
+                if let Some(collider) = env.collider_mut(ColliderId(i)) {
+                    *collider = new.clone();
+                }
+            }
+        }
+        for (i, (new, old)) in self.fields.iter().zip(&previous.fields).enumerate() {
+            let id = FieldId(i);
+            if new.force != old.force {
+                if let Some(field) = env.field_mut(id) {
+                    *field = new.force;
+                }
+            }
+            if new.enabled != old.enabled {
+                env.set_field_enabled(id, new.enabled);
+            }
+        }
+    }
+}
+
+fn line_of(text: &str, span: Option<Range<usize>>) -> usize {
+    let offset = span.map_or(0, |span| span.start).min(text.len());
+    text[..offset].matches('\n').count() + 1
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    #[serde(default)]
+    world: WorldEntry,
+    #[serde(default)]
+    rope: Vec<RopeEntry>,
+    #[serde(default)]
+    collider: Vec<ColliderEntry>,
+    #[serde(default)]
+    field: Vec<FieldEntry>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorldEntry {
+    #[serde(default)]
+    rope_collision: bool,
+    sleeping: Option<Spanned<SleepThreshold>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RopeEntry {
+    segments: Option<Spanned<usize>>,
+    shape: Spanned<RopeShapeEntry>,
+    params: Option<Spanned<ParamsEntry>>,
+    integrator: Option<Spanned<String>>,
+    solver: Option<Spanned<String>>,
+    #[serde(default)]
+    self_collision: bool,
+    breaking_limit: Option<Spanned<BreakingLimit>>,
+    #[serde(default)]
+    pins: Vec<Spanned<usize>>,
+    #[serde(default)]
+    follow_cursor: bool,
+}
+
+/// A preset, `default` unless named, with any of its values overridden.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ParamsEntry {
+    preset: Option<String>,
+    mass: Option<f64>,
+    stiffness: Option<f64>,
+    damping: Option<f64>,
+    length: Option<f64>,
+    static_friction: Option<f64>,
+    kinetic_friction: Option<f64>,
+    speed_cap: Option<f64>,
+    force_cap: Option<f64>,
+}
+
+impl ParamsEntry {
+    fn resolve(self) -> Result<RopeParams, String> {
+        let mut params = match &self.preset {
+            Some(name) => RopeParams::preset(name).ok_or_else(|| {
+                format!(
+                    "unknown preset {}, expected one of {}",
+                    name,
+                    RopeParams::PRESETS.join(", ")
+                )
+            })?,
+            None => RopeParams::default(),
+        };
+        let overrides = [
+            (&mut params.mass, self.mass),
+            (&mut params.stiffness, self.stiffness),
+            (&mut params.damping, self.damping),
+            (&mut params.length, self.length),
+            (&mut params.static_friction, self.static_friction),
+            (&mut params.kinetic_friction, self.kinetic_friction),
+            (&mut params.speed_cap, self.speed_cap),
+            (&mut params.force_cap, self.force_cap),
+        ];
+        for (value, new_value) in overrides {
+            if let Some(new_value) = new_value {
+                *value = new_value;
+            }
+        }
+        params.validate().map_err(|err| err.to_string())?;
+        Ok(params)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+enum RopeShapeEntry {
+    Line {
+        from: Vec2,
+        to: Vec2,
+    },
+    Polyline {
+        points: Vec<Vec2>,
+    },
+    Arc {
+        center: Vec2,
+        radius: f64,
+        from: f64,
+        to: f64,
+    },
+    Spiral {
+        center: Vec2,
+        inner_radius: f64,
+        outer_radius: f64,
+        turns: f64,
+        #[serde(default)]
+        angle: f64,
+    },
+    Bezier {
+        from: Vec2,
+        controls: [Vec2; 2],
+        to: Vec2,
+    },
+}
+
+impl From<RopeShapeEntry> for RopeShape {
+    fn from(entry: RopeShapeEntry) -> RopeShape {
+        match entry {
+            RopeShapeEntry::Line { from, to } => RopeShape::Line { from, to },
+            RopeShapeEntry::Polyline { points } => RopeShape::Polyline { points },
+            RopeShapeEntry::Arc {
+                center,
+                radius,
+                from,
+                to,
+            } => RopeShape::Arc {
+                center,
+                radius,
+                from: from.to_radians(),
+                to: to.to_radians(),
+            },
+            RopeShapeEntry::Spiral {
+                center,
+                inner_radius,
+                outer_radius,
+                turns,
+                angle,
+            } => RopeShape::Spiral {
+                center,
+                inner_radius,
+                outer_radius,
+                turns,
+                angle: angle.to_radians(),
+            },
+            RopeShapeEntry::Bezier { from, controls, to } => {
+                RopeShape::Bezier { from, controls, to }
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColliderEntry {
+    shape: Spanned<ShapeEntry>,
+    restitution: Option<Spanned<f64>>,
+    friction: Option<Spanned<f64>>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+enum ShapeEntry {
+    Circle {
+        center: Vec2,
+        radius: f64,
+    },
+    Box {
+        center: Vec2,
+        half_extents: Vec2,
+        #[serde(default)]
+        angle: f64,
+    },
+    Segment {
+        a: Vec2,
+        b: Vec2,
+    },
+    Polygon {
+        points: Vec<Vec2>,
+    },
+}
+
+impl ShapeEntry {
+    fn check(&self) -> Result<(), String> {
+        match self {
+            ShapeEntry::Circle { radius, .. } if *radius <= 0.0 => {
+                Err("a circle needs a positive radius".to_string())
+            }
+            ShapeEntry::Box { half_extents, .. }
+                if half_extents.x <= 0.0 || half_extents.y <= 0.0 =>
+            {
+                Err("a box needs positive half extents".to_string())
+            }
+            ShapeEntry::Polygon { points } if points.len() < 3 => {
+                Err("a polygon needs at least 3 points".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<ShapeEntry> for Shape {
+    fn from(entry: ShapeEntry) -> Shape {
+        match entry {
+            ShapeEntry::Circle { center, radius } => Shape::Circle { center, radius },
+            ShapeEntry::Box {
+                center,
+                half_extents,
+                angle,
+            } => Shape::Box {
+                center,
+                half_extents,
+                angle: angle.to_radians(),
+            },
+            ShapeEntry::Segment { a, b } => Shape::Segment { a, b },
+            ShapeEntry::Polygon { points } => Shape::Polygon { points },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FieldEntry {
+    force: Spanned<ForceEntry>,
+    #[serde(default = "enabled_by_default")]
+    enabled: bool,
+    key: Option<Spanned<String>>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+enum ForceEntry {
+    Gravity {
+        acceleration: Option<Vec2>,
+    },
+    Wind {
+        velocity: Vec2,
+        drag: f64,
+        #[serde(default)]
+        gust_strength: f64,
+        #[serde(default)]
+        gust_period: f64,
+    },
+    Radial {
+        center: Vec2,
+        strength: f64,
+        radius: f64,
+    },
+    Vortex {
+        center: Vec2,
+        strength: f64,
+        radius: f64,
+    },
+}
+
+impl ForceEntry {
+    fn check(&self) -> Result<(), String> {
+        match self {
+            ForceEntry::Wind {
+                drag, gust_period, ..
+            } if *drag < 0.0 || *gust_period < 0.0 => {
+                Err("wind needs a non-negative drag and gust period".to_string())
+            }
+            ForceEntry::Radial { radius, .. } | ForceEntry::Vortex { radius, .. }
+                if *radius <= 0.0 =>
+            {
+                Err("a field needs a positive radius".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<ForceEntry> for ForceField {
+    fn from(entry: ForceEntry) -> ForceField {
+        match entry {
+            ForceEntry::Gravity { acceleration } => match acceleration {
+                Some(acceleration) => ForceField::Gravity(acceleration),
+                None => ForceField::gravity(),
+            },
+            ForceEntry::Wind {
+                velocity,
+                drag,
+                gust_strength,
+                gust_period,
+            } => ForceField::Wind {
+                velocity,
+                drag,
+                gust_strength,
+                gust_period,
+            },
+            ForceEntry::Radial {
+                center,
+                strength,
+                radius,
+            } => ForceField::Radial {
+                center,
+                strength,
+                radius,
+            },
+            ForceEntry::Vortex {
+                center,
+                strength,
+                radius,
+            } => ForceField::Vortex {
+                center,
+                strength,
+                radius,
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl From<io::Error> for SceneError {
+    fn from(err: io::Error) -> SceneError {
+        SceneError::Io(err)
+    }
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Io(err) => write!(f, "{}", err),
+            SceneError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl Error for SceneError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_line(text: &str) -> usize {
+        match Scene::parse(text) {
+            Err(SceneError::Parse { line, .. }) => line,
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_a_scene() {
+        let scene = Scene::parse(
+            r#"
+            [world]
+            rope_collision = true
+            sleeping = { energy = 0.001, duration = 1.0 }
+
+            [[rope]]
+            segments = 30
+            shape = { kind = "arc", center = [640, 200], radius = 150, from = 180, to = 360 }
+            params = { preset = "chain", damping = 0.02 }
+            pins = [0, 29]
+
+            [[collider]]
+            shape = { kind = "circle", center = [640, 500], radius = 60 }
+            restitution = 0.5
+
+            [[field]]
+            force = { kind = "gravity" }
+            key = "G"
+            "#,
+        )
+        .unwrap();
+        assert!(scene.rope_collision);
+        assert_eq!(scene.ropes.len(), 1);
+        assert_eq!(scene.ropes[0].pins, [0, 29]);
+        assert_eq!(scene.ropes[0].params.damping, 0.02);
+        assert_eq!(scene.colliders[0].restitution, 0.5);
+        assert_eq!(scene.fields[0].key.as_deref(), Some("G"));
+        assert_eq!(scene.fields[0].key_line, 18);
+        assert_eq!(scene.build().rope_count(), 1);
+    }
+
+    #[test]
+    fn link_length_decides_topology_without_a_segment_count() {
+        let rope = |extra: &str| {
+            Scene::parse(&format!(
+                "[[rope]]\nshape = {{ kind = \"line\", from = [0, 0], to = [100, 0] }}\n{}",
+                extra
+            ))
+            .unwrap()
+        };
+        let short_links = rope("params = { length = 10 }\n");
+        assert!(!short_links.same_topology(&rope("")));
+        assert!(rope("params = { mass = 2 }\n").same_topology(&rope("")));
+        let counted = rope("segments = 5\n");
+        assert!(rope("segments = 5\nparams = { length = 10 }\n").same_topology(&counted));
+    }
+
+    #[test]
+    fn reports_syntax_errors_on_their_line() {
+        assert_eq!(
+            error_line("[world]\nrope_collision = true\nsleeping = {\n"),
+            3
+        );
+        assert_eq!(error_line("[world]\nrope_colision = true\n"), 2);
+    }
+
+    #[test]
+    fn reports_invalid_ropes_on_their_line() {
+        let rope = "[[rope]]\nshape = { kind = \"line\", from = [0, 0], to = [100, 0] }\n";
+        assert_eq!(error_line(&format!("{}integrator = \"rk5\"\n", rope)), 3);
+        assert_eq!(
+            error_line(&format!("{}segments = 5\npins = [0, 5]\n", rope)),
+            4
+        );
+        assert_eq!(
+            error_line(&format!("{}params = {{ mass = -1 }}\n", rope)),
+            3
+        );
+        assert_eq!(
+            error_line(&format!(
+                "{}breaking_limit = {{ tension = -5, duration = -1 }}\n",
+                rope
+            )),
+            3
+        );
+    }
+
+    #[test]
+    fn reports_invalid_colliders_on_their_line() {
+        let collider =
+            "[[collider]]\nshape = { kind = \"circle\", center = [0, 0], radius = 10 }\n";
+        assert_eq!(error_line(&format!("{}restitution = 1.5\n", collider)), 3);
+        assert_eq!(error_line(&format!("{}friction = -0.1\n", collider)), 3);
+        assert_eq!(
+            error_line(
+                "\n[[collider]]\nshape = { kind = \"circle\", center = [0, 0], radius = -1 }\n"
+            ),
+            3
+        );
+        assert_eq!(
+            error_line("[[collider]]\nshape = { kind = \"polygon\", points = [[0, 0], [1, 1]] }\n"),
+            2
+        );
+    }
+
+    #[test]
+    fn reports_invalid_fields_and_sleeping_on_their_line() {
+        assert_eq!(
+            error_line("[[field]]\nforce = { kind = \"radial\", center = [0, 0], strength = 1, radius = 0 }\n"),
+            2
+        );
+        assert_eq!(
+            error_line("[world]\nsleeping = { energy = 0.001, duration = 0 }\n"),
+            2
+        );
+    }
+}
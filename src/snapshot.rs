@@ -0,0 +1,284 @@
+use crate::world::World;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// Version of the snapshot format. Bump it whenever the saved state changes shape.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Leads every binary snapshot, followed by the version as a little-endian `u32`.
+const MAGIC: &[u8; 4] = b"ROPE";
+
+#[derive(Serialize)]
+struct SnapshotRef<'a> {
+    version: u32,
+    world: &'a World,
+}
+
+#[derive(Deserialize)]
+struct Snapshot {
+    world: World,
+}
+
+#[derive(Deserialize)]
+struct VersionOnly {
+    version: u32,
+}
+
+/// Saving and loading the complete state of a world, down to cursors, timers and
+/// pending events, so that a loaded world steps exactly like the saved one would have.
+///
+/// JSON cannot hold infinite or NaN numbers; use the binary form for worlds with those.
+impl World {
+    pub fn save_json(&self, out: impl Write) -> Result<(), SnapshotError> {
+        let snapshot = SnapshotRef {
+            version: SNAPSHOT_VERSION,
+            world: self,
+        };
+        serde_json::to_writer_pretty(out, &snapshot).map_err(SnapshotError::Json)
+    }
+
+    pub fn load_json(input: impl Read) -> Result<World, SnapshotError> {
+        let value: serde_json::Value = serde_json::from_reader(input)?;
+        let version = VersionOnly::deserialize(&value)?.version;
+        check_version(version)?;
+        checked(Snapshot::deserialize(value)?.world)
+    }
+
+    pub fn save_binary(&self, mut out: impl Write) -> Result<(), SnapshotError> {
+        out.write_all(MAGIC)?;
+        out.write_all(&SNAPSHOT_VERSION.to_le_bytes())?;
+        bincode::serialize_into(out, self).map_err(SnapshotError::Binary)
+    }
+
+    pub fn load_binary(mut input: impl Read) -> Result<World, SnapshotError> {
+        let mut header = [0; 8];
+        input.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(SnapshotError::NotASnapshot);
+        }
+        let mut version = [0; 4];
+        version.copy_from_slice(&header[4..]);
+        check_version(u32::from_le_bytes(version))?;
+        checked(bincode::deserialize_from(input).map_err(SnapshotError::Binary)?)
+    }
+}
+
+/// Rejects a loaded world that would misbehave or panic once stepped.
+fn checked(world: World) -> Result<World, SnapshotError> {
+    world.check().map_err(SnapshotError::Invalid)?;
+    Ok(world)
+}
+
+fn check_version(version: u32) -> Result<(), SnapshotError> {
+    if version == SNAPSHOT_VERSION {
+        Ok(())
+    } else {
+        Err(SnapshotError::Version { found: version })
+    }
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Binary(bincode::Error),
+    NotASnapshot,
+    Version {
+        found: u32,
+    },
+    /// Loaded fine but holds a world no method could have built.
+    Invalid(String),
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(err: io::Error) -> SnapshotError {
+        SnapshotError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(err: serde_json::Error) -> SnapshotError {
+        SnapshotError::Json(err)
+    }
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Io(err) => write!(f, "{}", err),
+            SnapshotError::Json(err) => write!(f, "{}", err),
+            SnapshotError::Binary(err) => write!(f, "{}", err),
+            SnapshotError::NotASnapshot => write!(f, "not a rope snapshot"),
+            SnapshotError::Version { found } => write!(
+                f,
+                "snapshot version {} is not supported, expected {}",
+                found, SNAPSHOT_VERSION
+            ),
+            SnapshotError::Invalid(message) => write!(f, "invalid snapshot: {}", message),
+        }
+    }
+}
+
+impl Error for SnapshotError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::Body;
+    use crate::collision::{Collider, Shape};
+    use crate::environment::Environment;
+    use crate::field::ForceField;
+    use crate::integrator;
+    use crate::joint::{Joint, JointEnd};
+    use crate::math::Vec2;
+    use crate::params::RopeParams;
+    use crate::physics::{BreakingLimit, Rope, Winch, NOMINAL_DT};
+    use crate::solver::Solver;
+    use crate::world::SleepThreshold;
+
+    /// A world using every part of the saved state, stepped until it is well under way.
+    fn busy_world() -> World {
+        let mut env = Environment::new();
+        env.add_field(ForceField::gravity());
+        env.add_field(ForceField::Wind {
+            velocity: Vec2::new(0.5, 0.0),
+            drag: 0.01,
+            gust_strength: 0.8,
+            gust_period: 3.0,
+        });
+        env.add_collider(Collider::new(Shape::Circle {
+            center: Vec2::new(420.0, 300.0),
+            radius: 40.0,
+        }));
+        let mut world = World::new(env);
+        world.set_rope_collision(true);
+        world.set_sleeping(Some(SleepThreshold {
+            energy: 1e-4,
+            duration: 0.5,
+        }));
+        let mut a = Rope::new(30, Vec2::new(400.0, 100.0), RopeParams::default()).unwrap();
+        a.set_integrator(integrator::by_name("rk4").unwrap());
+        a.pin(0, Vec2::new(400.0, 100.0));
+        a.set_cursor(Vec2::new(500.0, 50.0));
+        a.set_breaking_limit(Some(BreakingLimit {
+            tension: 3.0,
+            duration: 0.2,
+        }));
+        let a = world.add_rope(a);
+        let mut b = Rope::new(20, Vec2::new(300.0, 100.0), RopeParams::chain()).unwrap();
+        b.set_solver(Solver::xpbd());
+        b.set_winch(Winch {
+            speed: 0.05,
+            max_length: 600.0,
+        });
+        let b = world.add_rope(b);
+        let body = Body::rectangle(Vec2::new(300.0, 200.0), Vec2::new(20.0, 10.0), 3.0);
+        let body = world.add_body(body);
+        let tail = JointEnd::Segment { rope: b, index: 19 };
+        let local = Vec2::ZERO;
+        world
+            .add_joint(Joint::revolute(tail, JointEnd::Body { body, local }))
+            .unwrap();
+        let tail = JointEnd::Segment { rope: a, index: 29 };
+        let anchor = JointEnd::Anchor(Vec2::new(450.0, 400.0));
+        world.add_joint(Joint::revolute(tail, anchor)).unwrap();
+        for _ in 0..1000 {
+            world.step(NOMINAL_DT);
+        }
+        world
+    }
+
+    /// Steps the saved and the loaded world side by side and checks they stay identical.
+    fn assert_steps_alike(mut saved: World, mut loaded: World) {
+        assert_eq!(saved.checksum(), loaded.checksum());
+        for _ in 0..1000 {
+            saved.step(NOMINAL_DT);
+            loaded.step(NOMINAL_DT);
+        }
+        assert_eq!(saved.checksum(), loaded.checksum());
+        assert!(saved.drain_events().eq(loaded.drain_events()));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let world = busy_world();
+        let mut json = Vec::new();
+        world.save_json(&mut json).unwrap();
+        let loaded = World::load_json(&json[..]).unwrap();
+        assert_steps_alike(world, loaded);
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let world = busy_world();
+        let mut binary = Vec::new();
+        world.save_binary(&mut binary).unwrap();
+        let loaded = World::load_binary(&binary[..]).unwrap();
+        assert_steps_alike(world, loaded);
+    }
+
+    #[test]
+    fn json_with_another_version_is_rejected() {
+        let mut json = Vec::new();
+        World::new(Environment::new()).save_json(&mut json).unwrap();
+        let mut value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        value["version"] = (SNAPSHOT_VERSION + 1).into();
+        let json = serde_json::to_vec(&value).unwrap();
+        match World::load_json(&json[..]) {
+            Err(SnapshotError::Version { found }) => assert_eq!(found, SNAPSHOT_VERSION + 1),
+            other => panic!("expected a version error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn binary_with_another_version_is_rejected() {
+        let mut binary = Vec::new();
+        World::new(Environment::new())
+            .save_binary(&mut binary)
+            .unwrap();
+        binary[4..8].copy_from_slice(&(SNAPSHOT_VERSION + 1).to_le_bytes());
+        match World::load_binary(&binary[..]) {
+            Err(SnapshotError::Version { found }) => assert_eq!(found, SNAPSHOT_VERSION + 1),
+            other => panic!("expected a version error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    /// Loads `busy_world` saved as JSON after `edit` changed it by hand.
+    fn load_edited(edit: impl FnOnce(&mut serde_json::Value)) -> Result<World, SnapshotError> {
+        let mut json = Vec::new();
+        busy_world().save_json(&mut json).unwrap();
+        let mut value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        edit(&mut value["world"]);
+        World::load_json(&serde_json::to_vec(&value).unwrap()[..])
+    }
+
+    #[test]
+    fn json_with_invalid_state_is_rejected() {
+        let edits: [fn(&mut serde_json::Value); 5] = [
+            |world| world["ropes"][0][1]["pins"][0]["index"] = 999.into(),
+            |world| world["ropes"][0][1]["params"]["mass"] = (-1.0).into(),
+            |world| world["ropes"][1][1]["segments"] = serde_json::json!([]),
+            |world| world["ropes"][1][0] = 0.into(),
+            |world| world["next_joint_id"] = 1.into(),
+        ];
+        for edit in edits {
+            assert!(matches!(load_edited(edit), Err(SnapshotError::Invalid(_))));
+        }
+        assert!(load_edited(|_| {}).is_ok());
+    }
+
+    #[test]
+    fn binary_without_magic_is_rejected() {
+        let mut binary = Vec::new();
+        World::new(Environment::new())
+            .save_binary(&mut binary)
+            .unwrap();
+        binary[..4].copy_from_slice(b"EPOR");
+        assert!(matches!(
+            World::load_binary(&binary[..]),
+            Err(SnapshotError::NotASnapshot)
+        ));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::math::{Vec2, ZERO_THRESHOLD};
+use crate::physics::RopeSegment;
+use serde::{Deserialize, Serialize};
+
+/// How a rope keeps its links at rest length.
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum Solver {
+    /// Links are damped springs that pull stretched segments back together.
+    #[default]
+    Springs,
+    /// Extended position based dynamics: links are distance constraints projected after
+    /// every tick. `compliance` is the inverse stiffness, zero makes links inextensible.
+    Xpbd { compliance: f64, iterations: usize },
+}
+
+impl Solver {
+    pub fn xpbd() -> Solver {
+        Solver::Xpbd {
+            compliance: 0.0,
+            iterations: 8,
+        }
+    }
+}
+
+/// Projects every stretched link of the rope back to its rest length, moving segments in
+/// proportion to their inverse mass. `dt` is in ticks, matching the compliance units.
+///
+/// Returns the accumulated Lagrange multiplier of every link, `-lambda / dt²` being the
+/// force the link exerted.
+pub(crate) fn project_links(
+    segments: &mut [RopeSegment],
+    inverse_masses: &[f64],
+    lengths: &[f64],
+    compliance: f64,
+    iterations: usize,
+    dt: f64,
+) -> Vec<f64> {
+    let alpha = compliance / (dt * dt);
+    let mut lambdas = vec![0.0; segments.len().saturating_sub(1)];
+    for _ in 0..iterations {
+        for (i, lambda) in lambdas.iter_mut().enumerate() {
+            let (w1, w2) = (inverse_masses[i], inverse_masses[i + 1]);
+            let offset = segments[i + 1].pos - segments[i].pos;
+            let distance = offset.length();
+            let stretch = distance - lengths[i];
+            if stretch <= 0.0 || distance < ZERO_THRESHOLD || w1 + w2 == 0.0 {
+                continue;
+            }
+            let delta = (-stretch - alpha * *lambda) / (w1 + w2 + alpha);
+            *lambda += delta;
+            let correction: Vec2 = offset / distance * delta;
+            segments[i].pos -= correction * w1;
+            segments[i + 1].pos += correction * w2;
+        }
+    }
+    lambdas
+}
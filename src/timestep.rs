@@ -0,0 +1,40 @@
+/// Accumulates frame time and hands it out in fixed simulation steps.
+#[derive(Debug, Clone)]
+pub struct FixedTimestep {
+    dt: f64,
+    max_steps: usize,
+    accumulator: f64,
+}
+
+impl FixedTimestep {
+    pub fn new(dt: f64, max_steps: usize) -> FixedTimestep {
+        FixedTimestep {
+            dt,
+            max_steps,
+            accumulator: 0.0,
+        }
+    }
+
+    pub fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    /// Adds `elapsed` seconds and returns how many steps should be simulated.
+    /// Time beyond `max_steps` steps is dropped so a slow frame can't snowball.
+    pub fn advance(&mut self, elapsed: f64) -> usize {
+        self.accumulator += elapsed;
+        let steps = (self.accumulator / self.dt).floor() as usize;
+        if steps > self.max_steps {
+            self.accumulator = 0.0;
+            self.max_steps
+        } else {
+            self.accumulator -= steps as f64 * self.dt;
+            steps
+        }
+    }
+
+    /// How far the leftover time is into the next step, in `0..1`.
+    pub fn alpha(&self) -> f64 {
+        (self.accumulator / self.dt).min(1.0)
+    }
+}
@@ -0,0 +1,909 @@
+use crate::body::{Body, BodyId};
+use crate::broadphase::SpatialHash;
+use crate::collision::CapsuleContact;
+use crate::environment::Environment;
+use crate::event::{Event, RopeEvent};
+use crate::joint::{self, Joint, JointEnd, JointId};
+use crate::math::{self, Vec2, ZERO_THRESHOLD};
+use crate::physics::{Rope, NOMINAL_DT};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RopeId(pub(crate) u64);
+
+/// First or last segment of a rope.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RopeEnd {
+    Head,
+    Tail,
+}
+
+/// Puts ropes and bodies to sleep once their kinetic energy stays below `energy` for
+/// `duration` seconds. Everything joined together sleeps and wakes as one island.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SleepThreshold {
+    pub energy: f64,
+    pub duration: f64,
+}
+
+/// Owns every rope and rigid body in a scene together with the environment they share
+/// and the joints connecting them.
+///
+/// Ropes are stepped in the order they were added, so a scene plays out the same way every run.
+#[derive(Default, Serialize, Deserialize)]
+pub struct World {
+    env: Environment,
+    ropes: Vec<(RopeId, Rope)>,
+    next_id: u64,
+    bodies: Vec<(BodyId, Body)>,
+    next_body_id: u64,
+    joints: Vec<(JointId, Joint)>,
+    next_joint_id: u64,
+    rope_collision: bool,
+    events: Vec<Event>,
+    sleeping: Option<SleepThreshold>,
+    wake_all: bool,
+}
+
+impl World {
+    const JOINT_ITERATIONS: usize = 4;
+
+    pub fn new(env: Environment) -> World {
+        World {
+            env,
+            ropes: Vec::new(),
+            next_id: 0,
+            bodies: Vec::new(),
+            next_body_id: 0,
+            joints: Vec::new(),
+            next_joint_id: 0,
+            rope_collision: false,
+            events: Vec::new(),
+            sleeping: None,
+            wake_all: false,
+        }
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.env
+    }
+
+    /// Changing fields or colliders wakes everything up.
+    pub fn environment_mut(&mut self) -> &mut Environment {
+        self.wake_all = true;
+        &mut self.env
+    }
+
+    pub fn add_rope(&mut self, rope: Rope) -> RopeId {
+        let id = RopeId(self.next_id);
+        self.next_id += 1;
+        self.ropes.push((id, rope));
+        id
+    }
+
+    /// Removes a rope along with every joint attached to it.
+    pub fn remove_rope(&mut self, id: RopeId) -> Option<Rope> {
+        let index = self.index_of(id)?;
+        self.joints.retain(|(_, joint)| !joint.involves(id));
+        Some(self.ropes.remove(index).1)
+    }
+
+    pub fn rope(&self, id: RopeId) -> Option<&Rope> {
+        self.index_of(id).map(|i| &self.ropes[i].1)
+    }
+
+    pub fn rope_mut(&mut self, id: RopeId) -> Option<&mut Rope> {
+        self.index_of(id).map(move |i| &mut self.ropes[i].1)
+    }
+
+    pub fn ropes(&self) -> impl Iterator<Item = (RopeId, &Rope)> {
+        self.ropes.iter().map(|(id, rope)| (*id, rope))
+    }
+
+    pub fn ropes_mut(&mut self) -> impl Iterator<Item = (RopeId, &mut Rope)> {
+        self.ropes.iter_mut().map(|(id, rope)| (*id, rope))
+    }
+
+    pub fn rope_count(&self) -> usize {
+        self.ropes.len()
+    }
+
+    /// Cuts link `link` of a rope, see `Rope::split_off`. Joints on the cut-off segments
+    /// move with them to the new rope, whose id is returned.
+    pub fn cut_rope(&mut self, id: RopeId, link: usize) -> Option<RopeId> {
+        let rope = self.rope_mut(id)?;
+        if link + 1 >= rope.segments().len() {
+            return None;
+        }
+        let tail = rope.split_off(link);
+        let tail_id = self.add_rope(tail);
+        self.remap_segments(id, |index| {
+            if index > link {
+                (tail_id, index - link - 1)
+            } else {
+                (id, index)
+            }
+        });
+        Some(tail_id)
+    }
+
+    /// Links end `a_end` of rope `a` to end `b_end` of rope `b`, merging `b` into `a`.
+    /// Joints on `b` move over to `a`. Returns false if either rope is missing or both
+    /// ids are the same rope.
+    pub fn join_ropes(&mut self, a: RopeId, a_end: RopeEnd, b: RopeId, b_end: RopeEnd) -> bool {
+        if a == b || self.rope(a).is_none() || self.rope(b).is_none() {
+            return false;
+        }
+        if a_end == RopeEnd::Head {
+            self.reverse_rope(a);
+        }
+        if b_end == RopeEnd::Tail {
+            self.reverse_rope(b);
+        }
+        let offset = self.rope(a).unwrap().segments().len();
+        let other = self.ropes.remove(self.index_of(b).unwrap()).1;
+        self.rope_mut(a).unwrap().join(other);
+        self.remap_segments(b, |index| (a, index + offset));
+        true
+    }
+
+    /// Cuts every link crossing the line from `from` to `to` and returns the ids of the
+    /// ropes split off.
+    pub fn slice(&mut self, from: Vec2, to: Vec2) -> Vec<RopeId> {
+        let mut cuts = Vec::new();
+        for (id, rope) in &self.ropes {
+            for (link, pair) in rope.segments().windows(2).enumerate().rev() {
+                let (a, b) = (pair[0].pos(), pair[1].pos());
+                let (s, t) = math::closest_points_between_segments(from, to, a, b);
+                if (from.lerp(to, s) - a.lerp(b, t)).length() < ZERO_THRESHOLD {
+                    cuts.push((*id, link));
+                }
+            }
+        }
+        cuts.into_iter()
+            .filter_map(|(id, link)| self.cut_rope(id, link))
+            .collect()
+    }
+
+    pub fn add_body(&mut self, body: Body) -> BodyId {
+        let id = BodyId(self.next_body_id);
+        self.next_body_id += 1;
+        self.bodies.push((id, body));
+        id
+    }
+
+    /// Removes a body along with every joint attached to it.
+    pub fn remove_body(&mut self, id: BodyId) -> Option<Body> {
+        let index = self.body_index(id)?;
+        self.joints.retain(|(_, joint)| !joint.involves_body(id));
+        Some(self.bodies.remove(index).1)
+    }
+
+    pub fn body(&self, id: BodyId) -> Option<&Body> {
+        self.body_index(id).map(|i| &self.bodies[i].1)
+    }
+
+    pub fn body_mut(&mut self, id: BodyId) -> Option<&mut Body> {
+        self.body_index(id).map(move |i| &mut self.bodies[i].1)
+    }
+
+    pub fn bodies(&self) -> impl Iterator<Item = (BodyId, &Body)> {
+        self.bodies.iter().map(|(id, body)| (*id, body))
+    }
+
+    /// Connects two joint ends. Returns `None` if an end refers to a missing rope or
+    /// segment, or the joint isn't valid.
+    pub fn add_joint(&mut self, joint: Joint) -> Option<JointId> {
+        if !joint.is_valid()
+            || self.end_position(joint.a).is_none()
+            || self.end_position(joint.b).is_none()
+        {
+            return None;
+        }
+        let id = JointId(self.next_joint_id);
+        self.next_joint_id += 1;
+        self.joints.push((id, joint));
+        self.disturb(joint.a);
+        self.disturb(joint.b);
+        Some(id)
+    }
+
+    pub fn remove_joint(&mut self, id: JointId) -> Option<Joint> {
+        let index = self
+            .joints
+            .iter()
+            .position(|(joint_id, _)| *joint_id == id)?;
+        let joint = self.joints.remove(index).1;
+        self.disturb(joint.a);
+        self.disturb(joint.b);
+        Some(joint)
+    }
+
+    pub fn joint(&self, id: JointId) -> Option<&Joint> {
+        self.joints
+            .iter()
+            .find(|(joint_id, _)| *joint_id == id)
+            .map(|(_, joint)| joint)
+    }
+
+    pub fn joints(&self) -> impl Iterator<Item = (JointId, &Joint)> {
+        self.joints.iter().map(|(id, joint)| (*id, joint))
+    }
+
+    pub fn end_position(&self, end: JointEnd) -> Option<Vec2> {
+        match end {
+            JointEnd::Segment { rope, index } => self
+                .rope(rope)
+                .and_then(|rope| rope.segments().get(index))
+                .map(|segment| segment.pos()),
+            JointEnd::Body { body, local } => self.body(body).map(|body| body.world_point(local)),
+            JointEnd::Anchor(pos) => Some(pos),
+        }
+    }
+
+    pub fn rope_collision(&self) -> bool {
+        self.rope_collision
+    }
+
+    /// Makes links of different ropes push each other apart, so ropes can drape over
+    /// and tangle with one another. Each rope's own links are handled by its self-collision.
+    pub fn set_rope_collision(&mut self, enabled: bool) {
+        self.rope_collision = enabled;
+    }
+
+    pub fn sleeping(&self) -> Option<SleepThreshold> {
+        self.sleeping
+    }
+
+    /// Lets settled islands of ropes and bodies sleep. Sleeping ones are skipped by
+    /// `step` until something moves them: their cursor or pins, a collision with
+    /// something awake, a change to the environment or an explicit wake call.
+    pub fn set_sleeping(&mut self, threshold: Option<SleepThreshold>) {
+        self.sleeping = threshold;
+        self.wake_all = true;
+    }
+
+    pub fn wake_rope(&mut self, id: RopeId) {
+        if let Some(rope) = self.rope_mut(id) {
+            rope.disturbed = true;
+        }
+    }
+
+    pub fn wake_body(&mut self, id: BodyId) {
+        if let Some(body) = self.body_mut(id) {
+            body.disturbed = true;
+        }
+    }
+
+    /// Advances everything by `dt` seconds. Does nothing unless `dt` is positive.
+    pub fn step(&mut self, dt: f64) {
+        if dt.is_nan() || dt <= 0.0 {
+            return;
+        }
+        self.wake_islands();
+        let reeled = self.tick_ropes(dt);
+        // Winches add and remove segments right after the head.
+        for (id, by) in reeled {
+            self.remap_segments(id, |index| match index {
+                0 => (id, 0),
+                _ => (id, (index as isize + by).max(1) as usize),
+            });
+        }
+        self.collect_rope_events();
+        for (_, body) in &mut self.bodies {
+            if !body.asleep {
+                body.tick(dt, &self.env);
+            }
+        }
+        if self.rope_collision {
+            self.collide_ropes();
+        }
+        self.collide_ropes_with_bodies();
+        self.solve_joints(dt / NOMINAL_DT);
+        self.fall_asleep(dt);
+        self.env.advance(dt);
+    }
+
+    /// FNV-1a hash of the exact state of every rope and body, for telling whether two
+    /// runs of a scene diverged.
+    pub fn checksum(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut feed = |value: u64| {
+            for byte in value.to_le_bytes().iter() {
+                hash ^= u64::from(*byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+        for (id, rope) in &self.ropes {
+            feed(id.0);
+            feed(rope.segments().len() as u64);
+            for segment in rope.segments() {
+                let (pos, speed) = (segment.pos(), segment.speed());
+                for value in [pos.x, pos.y, speed.x, speed.y].iter() {
+                    feed(value.to_bits());
+                }
+            }
+        }
+        for (id, body) in &self.bodies {
+            feed(id.0);
+            let (pos, speed) = (body.pos(), body.speed());
+            for value in [
+                pos.x,
+                pos.y,
+                body.angle(),
+                speed.x,
+                speed.y,
+                body.angular_speed(),
+            ]
+            .iter()
+            {
+                feed(value.to_bits());
+            }
+        }
+        hash
+    }
+
+    /// Events from the steps since the last call, oldest first. Events queue up until
+    /// drained and are saved with snapshots, so drain them after every step even when
+    /// nothing reacts to them.
+    pub fn drain_events(&mut self) -> impl Iterator<Item = Event> + '_ {
+        self.events.drain(..)
+    }
+
+    /// Turns the events of every rope into world events, cutting ropes at the links
+    /// that broke.
+    fn collect_rope_events(&mut self) {
+        let mut broken = Vec::new();
+        for (rope, r) in &mut self.ropes {
+            let rope = *rope;
+            for event in r.take_events() {
+                self.events.push(match event {
+                    RopeEvent::TensionExceeded { link, tension } => Event::TensionExceeded {
+                        rope,
+                        link,
+                        tension,
+                    },
+                    RopeEvent::LinkBroken { link } => {
+                        broken.push((rope, link));
+                        continue;
+                    }
+                    RopeEvent::ContactBegan { segment, collider } => Event::ContactBegan {
+                        rope,
+                        segment,
+                        collider,
+                    },
+                    RopeEvent::ContactEnded { segment, collider } => Event::ContactEnded {
+                        rope,
+                        segment,
+                        collider,
+                    },
+                    RopeEvent::CameToRest => Event::CameToRest { rope },
+                });
+            }
+        }
+        // Cutting the highest link first keeps the lower link indices of a rope valid.
+        broken.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        for (rope, link) in broken {
+            if let Some(tail) = self.cut_rope(rope, link) {
+                self.events.push(Event::LinkBroken { rope, link, tail });
+            }
+        }
+    }
+
+    /// Ticks every awake rope and returns how many segments the ropes that were reeled
+    /// gained or lost. Ropes only read the shared environment, so with the `parallel`
+    /// feature they are ticked on a thread pool with exactly the same results. A pool of
+    /// one thread ticks them in order without handing them to it.
+    fn tick_ropes(&mut self, dt: f64) -> Vec<(RopeId, isize)> {
+        let env = &self.env;
+        let tick = |(id, rope): &mut (RopeId, Rope)| {
+            if rope.asleep {
+                return None;
+            }
+            let before = rope.segments().len();
+            rope.tick(dt, env);
+            let by = rope.segments().len() as isize - before as isize;
+            Some((*id, by)).filter(|_| by != 0)
+        };
+        #[cfg(feature = "parallel")]
+        if rayon::current_num_threads() > 1 {
+            return self.ropes.par_iter_mut().filter_map(tick).collect();
+        }
+        self.ropes.iter_mut().filter_map(tick).collect()
+    }
+
+    /// Wakes every island with a member that was moved or pushed since the last step.
+    fn wake_islands(&mut self) {
+        let islands = self.islands();
+        let mut woken = vec![false; islands.len()];
+        let members = self.ropes.iter().map(|(_, rope)| rope.disturbed);
+        let members = members.chain(self.bodies.iter().map(|(_, body)| body.disturbed));
+        for (i, disturbed) in members.enumerate() {
+            if disturbed || self.wake_all || self.sleeping.is_none() {
+                woken[islands[i]] = true;
+            }
+        }
+        let (ropes, bodies) = islands.split_at(self.ropes.len());
+        for ((_, rope), island) in self.ropes.iter_mut().zip(ropes) {
+            if woken[*island] {
+                rope.asleep = false;
+                rope.calm_for = 0.0;
+            }
+        }
+        for ((_, body), island) in self.bodies.iter_mut().zip(bodies) {
+            if woken[*island] {
+                body.asleep = false;
+                body.calm_for = 0.0;
+            }
+        }
+        self.wake_all = false;
+    }
+
+    /// Puts islands whose members all stayed calm long enough to sleep.
+    fn fall_asleep(&mut self, dt: f64) {
+        for (_, rope) in &mut self.ropes {
+            rope.disturbed &= rope.asleep;
+        }
+        for (_, body) in &mut self.bodies {
+            body.disturbed &= body.asleep;
+        }
+        let threshold = match self.sleeping {
+            Some(threshold) => threshold,
+            None => return,
+        };
+        let islands = self.islands();
+        let mut settled = vec![true; islands.len()];
+        let (rope_islands, body_islands) = islands.split_at(self.ropes.len());
+        for ((_, rope), island) in self.ropes.iter_mut().zip(rope_islands) {
+            if rope.asleep {
+                continue;
+            }
+            let calm = rope.kinetic_energy() < threshold.energy && rope.winch().is_none();
+            rope.calm_for = if calm { rope.calm_for + dt } else { 0.0 };
+            settled[*island] &= rope.calm_for >= threshold.duration;
+        }
+        for ((_, body), island) in self.bodies.iter_mut().zip(body_islands) {
+            if body.asleep {
+                continue;
+            }
+            let calm = body.kinetic_energy() < threshold.energy;
+            body.calm_for = if calm { body.calm_for + dt } else { 0.0 };
+            settled[*island] &= body.calm_for >= threshold.duration;
+        }
+        for ((_, rope), island) in self.ropes.iter_mut().zip(rope_islands) {
+            if settled[*island] && !rope.asleep {
+                rope.asleep = true;
+                for segment in rope.segments_mut() {
+                    segment.speed = Vec2::ZERO;
+                    segment.prev_pos = segment.pos;
+                }
+            }
+        }
+        for ((_, body), island) in self.bodies.iter_mut().zip(body_islands) {
+            if settled[*island] && !body.asleep {
+                body.asleep = true;
+                body.speed = Vec2::ZERO;
+                body.angular_speed = 0.0;
+                body.prev_pos = body.pos;
+                body.prev_angle = body.angle;
+            }
+        }
+    }
+
+    /// Island of every rope followed by every body, as the index of a representative
+    /// member. Joints put their ends in the same island.
+    fn islands(&self) -> Vec<usize> {
+        fn root(parents: &mut [usize], mut i: usize) -> usize {
+            while parents[i] != i {
+                parents[i] = parents[parents[i]];
+                i = parents[i];
+            }
+            i
+        }
+        let mut parents: Vec<usize> = (0..self.ropes.len() + self.bodies.len()).collect();
+        for (_, joint) in &self.joints {
+            if let (Some(a), Some(b)) = (self.member(joint.a), self.member(joint.b)) {
+                let (a, b) = (root(&mut parents, a), root(&mut parents, b));
+                parents[a] = b;
+            }
+        }
+        (0..parents.len()).map(|i| root(&mut parents, i)).collect()
+    }
+
+    fn member(&self, end: JointEnd) -> Option<usize> {
+        match end {
+            JointEnd::Segment { rope, .. } => self.index_of(rope),
+            JointEnd::Body { body, .. } => self.body_index(body).map(|i| self.ropes.len() + i),
+            JointEnd::Anchor(_) => None,
+        }
+    }
+
+    fn disturb(&mut self, end: JointEnd) {
+        match end {
+            JointEnd::Segment { rope, .. } => self.wake_rope(rope),
+            JointEnd::Body { body, .. } => self.wake_body(body),
+            JointEnd::Anchor(_) => {}
+        }
+    }
+
+    fn is_asleep(&self, end: JointEnd) -> bool {
+        match end {
+            JointEnd::Segment { rope, .. } => self.rope(rope).is_some_and(|rope| rope.asleep),
+            JointEnd::Body { body, .. } => self.body(body).is_some_and(|body| body.asleep),
+            JointEnd::Anchor(_) => false,
+        }
+    }
+
+    fn solve_joints(&mut self, scale: f64) {
+        // Ropes and bodies stay where they are while joints are solved, so every end is
+        // looked up once per step rather than once per iteration.
+        let joints: Vec<(Joint, ResolvedEnd, ResolvedEnd)> = self
+            .joints
+            .iter()
+            .filter(|(_, joint)| !self.is_asleep(joint.a) && !self.is_asleep(joint.b))
+            .filter_map(|&(_, joint)| Some((joint, self.resolve(joint.a)?, self.resolve(joint.b)?)))
+            .collect();
+        for _ in 0..Self::JOINT_ITERATIONS {
+            for &(joint, a_end, b_end) in &joints {
+                let (a, a_frame) = match self.end_state(a_end) {
+                    Some(state) => state,
+                    None => continue,
+                };
+                let (b, _) = match self.end_state(b_end) {
+                    Some(state) => state,
+                    None => continue,
+                };
+                let error = joint.error(a, a_frame, b);
+                if error.length() < ZERO_THRESHOLD {
+                    continue;
+                }
+                let direction = error.normalized();
+                let wa = self.end_inverse_mass(a_end, a, direction);
+                let wb = self.end_inverse_mass(b_end, b, direction);
+                if wa + wb == 0.0 {
+                    continue;
+                }
+                let impulse = error / (wa + wb);
+                self.move_end(a_end, a, impulse, scale);
+                self.move_end(b_end, b, impulse * -1.0, scale);
+            }
+        }
+    }
+
+    fn resolve(&self, end: JointEnd) -> Option<ResolvedEnd> {
+        Some(match end {
+            JointEnd::Segment { rope, index } => ResolvedEnd::Segment {
+                rope: self.index_of(rope)?,
+                index,
+            },
+            JointEnd::Body { body, local } => ResolvedEnd::Body {
+                body: self.body_index(body)?,
+                local,
+            },
+            JointEnd::Anchor(pos) => ResolvedEnd::Anchor(pos),
+        })
+    }
+
+    /// Position and frame of a joint end.
+    fn end_state(&self, end: ResolvedEnd) -> Option<(Vec2, Vec2)> {
+        match end {
+            ResolvedEnd::Segment { rope, index } => {
+                let segments = self.ropes[rope].1.segments();
+                let segment = segments.get(index)?;
+                Some((segment.pos(), joint::frame_at(segments, index)))
+            }
+            ResolvedEnd::Body { body, local } => {
+                let body = &self.bodies[body].1;
+                Some((body.world_point(local), body.frame()))
+            }
+            ResolvedEnd::Anchor(pos) => Some((pos, Vec2::new(1.0, 0.0))),
+        }
+    }
+
+    fn end_inverse_mass(&self, end: ResolvedEnd, point: Vec2, direction: Vec2) -> f64 {
+        match end {
+            ResolvedEnd::Segment { rope, index } => self.ropes[rope].1.inverse_mass(index),
+            ResolvedEnd::Body { body, .. } => self.bodies[body].1.inverse_mass_at(point, direction),
+            ResolvedEnd::Anchor(_) => 0.0,
+        }
+    }
+
+    /// Moves a joint end as if `impulse` acted on it for one tick, and gives it the
+    /// matching speed.
+    fn move_end(&mut self, end: ResolvedEnd, point: Vec2, impulse: Vec2, scale: f64) {
+        match end {
+            ResolvedEnd::Segment { rope, index } => {
+                let rope = &mut self.ropes[rope].1;
+                let delta = impulse * rope.inverse_mass(index);
+                if let Some(segment) = rope.segments_mut().get_mut(index) {
+                    segment.pos += delta;
+                    segment.speed += delta / scale;
+                }
+            }
+            ResolvedEnd::Body { body, .. } => {
+                let body = &mut self.bodies[body].1;
+                body.displace(impulse, point);
+                body.apply_impulse(impulse / scale, point);
+            }
+            ResolvedEnd::Anchor(_) => {}
+        }
+    }
+
+    fn collide_ropes_with_bodies(&mut self) {
+        let radius = Rope::DRAW_WIDTH / 2.0;
+        for (_, body) in &mut self.bodies {
+            for (_, rope) in &mut self.ropes {
+                if body.asleep && rope.asleep {
+                    continue;
+                }
+                let mut touched = false;
+                for index in 0..rope.segments().len() {
+                    let ws = rope.inverse_mass(index);
+                    let segment = &mut rope.segments_mut()[index];
+                    let contact = match body.shape_in_world().contact(segment.pos, radius) {
+                        Some(contact) => contact,
+                        None => continue,
+                    };
+                    let normal = contact.normal;
+                    let point = segment.pos - normal * radius;
+                    let wb = body.inverse_mass_at(point, normal);
+                    let correction = normal * (contact.depth / (ws + wb));
+                    segment.pos += correction * ws;
+                    body.displace(correction * -1.0, point);
+                    touched = true;
+
+                    let normal_speed = (segment.speed - body.velocity_at(point)).dot(normal);
+                    if normal_speed < 0.0 {
+                        let impulse =
+                            normal * (-(1.0 + body.restitution) * normal_speed / (ws + wb));
+                        segment.speed += impulse * ws;
+                        body.apply_impulse(impulse * -1.0, point);
+                    }
+                }
+                if touched {
+                    rope.disturbed = true;
+                    body.disturbed = true;
+                }
+            }
+        }
+    }
+
+    fn collide_ropes(&mut self) {
+        let radius = Rope::DRAW_WIDTH / 2.0;
+        let cell_size = self
+            .ropes
+            .iter()
+            .map(|(_, rope)| rope.params().length)
+            .fold(0.0, f64::max)
+            + Rope::DRAW_WIDTH;
+        let mut grid = SpatialHash::new(cell_size);
+        for (r, (_, rope)) in self.ropes.iter().enumerate() {
+            for (i, link) in rope.segments().windows(2).enumerate() {
+                let (a, b) = (link[0].pos(), link[1].pos());
+                let min = Vec2::new(a.x.min(b.x) - radius, a.y.min(b.y) - radius);
+                let max = Vec2::new(a.x.max(b.x) + radius, a.y.max(b.y) + radius);
+                grid.insert((r, i), min, max);
+            }
+        }
+        let inverse_masses: Vec<Vec<f64>> = self
+            .ropes
+            .iter()
+            .map(|(_, rope)| rope.inverse_masses(rope.segments().len()))
+            .collect();
+        for ((ra, i), (rb, j)) in grid.pairs() {
+            if ra == rb {
+                continue;
+            }
+            let (left, right) = self.ropes.split_at_mut(rb);
+            if left[ra].1.asleep && right[0].1.asleep {
+                continue;
+            }
+            let a = left[ra].1.segments_mut();
+            let b = right[0].1.segments_mut();
+            let ends = [a[i].pos, a[i + 1].pos, b[j].pos, b[j + 1].pos];
+            let contact = match CapsuleContact::between(ends, radius) {
+                Some(contact) => contact,
+                None => continue,
+            };
+            let (pos_deltas, speed_deltas) = contact.resolve(
+                [a[i].speed, a[i + 1].speed, b[j].speed, b[j + 1].speed],
+                [
+                    inverse_masses[ra][i],
+                    inverse_masses[ra][i + 1],
+                    inverse_masses[rb][j],
+                    inverse_masses[rb][j + 1],
+                ],
+            );
+            let segments = a[i..i + 2].iter_mut().chain(b[j..j + 2].iter_mut());
+            for (k, segment) in segments.enumerate() {
+                segment.pos += pos_deltas[k];
+                segment.speed += speed_deltas[k];
+            }
+            left[ra].1.disturbed = true;
+            right[0].1.disturbed = true;
+        }
+    }
+
+    fn reverse_rope(&mut self, id: RopeId) {
+        let last = match self.rope_mut(id) {
+            Some(rope) => {
+                rope.reverse();
+                rope.segments().len() - 1
+            }
+            None => return,
+        };
+        self.remap_segments(id, |index| (id, last - index));
+    }
+
+    /// Points joint ends on segments of `rope` at the segment `remap` gives for their index.
+    fn remap_segments(&mut self, rope: RopeId, remap: impl Fn(usize) -> (RopeId, usize)) {
+        for (_, joint) in &mut self.joints {
+            for end in [&mut joint.a, &mut joint.b].iter_mut() {
+                if let JointEnd::Segment { rope: r, index } = **end {
+                    if r == rope {
+                        let (rope, index) = remap(index);
+                        **end = JointEnd::Segment { rope, index };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Finds state the methods above never produce but a hand-edited snapshot might: an
+    /// invalid rope, body or joint, ids out of order or not below the next id to hand out,
+    /// or a joint end that doesn't exist.
+    pub(crate) fn check(&self) -> Result<(), String> {
+        let rope_ids: Vec<u64> = self.ropes.iter().map(|(id, _)| id.0).collect();
+        let body_ids: Vec<u64> = self.bodies.iter().map(|(id, _)| id.0).collect();
+        let joint_ids: Vec<u64> = self.joints.iter().map(|(id, _)| id.0).collect();
+        for (kind, ids, next_id) in [
+            ("rope", rope_ids, self.next_id),
+            ("body", body_ids, self.next_body_id),
+            ("joint", joint_ids, self.next_joint_id),
+        ] {
+            if ids.windows(2).any(|pair| pair[0] >= pair[1]) {
+                return Err(format!("{} ids are not in increasing order", kind));
+            }
+            if ids.last().is_some_and(|&last| last >= next_id) {
+                return Err(format!("{} ids are not below the next {} id", kind, kind));
+            }
+        }
+        for (id, rope) in &self.ropes {
+            rope.check()
+                .map_err(|message| format!("rope {}: {}", id.0, message))?;
+        }
+        for (id, body) in &self.bodies {
+            if !(body.mass() > 0.0 && body.inertia() > 0.0) {
+                return Err(format!("body {}: mass must be positive", id.0));
+            }
+        }
+        for (id, joint) in &self.joints {
+            if !joint.is_valid() {
+                return Err(format!("joint {}: invalid limits", id.0));
+            }
+            if self.end_position(joint.a).is_none() || self.end_position(joint.b).is_none() {
+                return Err(format!("joint {}: an end doesn't exist", id.0));
+            }
+        }
+        Ok(())
+    }
+
+    /// Ropes and bodies are only ever appended with a new, higher id or removed, so both
+    /// lists stay sorted by id.
+    fn index_of(&self, id: RopeId) -> Option<usize> {
+        self.ropes
+            .binary_search_by_key(&id, |(rope_id, _)| *rope_id)
+            .ok()
+    }
+
+    fn body_index(&self, id: BodyId) -> Option<usize> {
+        self.bodies
+            .binary_search_by_key(&id, |(body_id, _)| *body_id)
+            .ok()
+    }
+}
+
+/// A joint end pointing at the index of its rope or body for the length of one step.
+#[derive(Copy, Clone)]
+enum ResolvedEnd {
+    Segment { rope: usize, index: usize },
+    Body { body: usize, local: Vec2 },
+    Anchor(Vec2),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sliders_with_crossed_limits_are_rejected() {
+        let mut world = World::new(Environment::new());
+        let a = JointEnd::Anchor(Vec2::ZERO);
+        let b = JointEnd::Anchor(Vec2::new(10.0, 0.0));
+        let axis = Vec2::new(1.0, 0.0);
+        assert!(world
+            .add_joint(Joint::slider(a, b, axis, 10.0, -10.0))
+            .is_none());
+        assert!(world
+            .add_joint(Joint::slider(a, b, axis, f64::NAN, 10.0))
+            .is_none());
+        assert!(world
+            .add_joint(Joint::slider(a, b, axis, -10.0, 10.0))
+            .is_some());
+        world.step(NOMINAL_DT);
+    }
+
+    #[cfg(feature = "parallel")]
+    mod parallel {
+        use super::*;
+        use crate::field::ForceField;
+        use crate::params::RopeParams;
+        use crate::solver::Solver;
+
+        const TANGLE_STEPS: usize = 2000;
+
+        /// Ropes that hang crosswise over each other, joined end to end and to a body.
+        fn tangle() -> World {
+            let mut env = Environment::new();
+            env.add_field(ForceField::gravity());
+            let mut world = World::new(env);
+            world.set_rope_collision(true);
+            let mut ids = Vec::new();
+            for i in 0..6 {
+                let x = 200.0 + 40.0 * i as f64;
+                let mut rope = Rope::new(20, Vec2::new(x, 100.0), RopeParams::default()).unwrap();
+                if i % 2 == 1 {
+                    rope.set_solver(Solver::xpbd());
+                }
+                rope.pin(0, Vec2::new(x, 100.0));
+                rope.set_cursor(Vec2::new(400.0 - x, 300.0));
+                ids.push(world.add_rope(rope));
+            }
+            for pair in ids.windows(2) {
+                let a = JointEnd::Segment {
+                    rope: pair[0],
+                    index: 19,
+                };
+                let b = JointEnd::Segment {
+                    rope: pair[1],
+                    index: 10,
+                };
+                world.add_joint(Joint::revolute(a, b)).unwrap();
+            }
+            let body = world.add_body(Body::circle(Vec2::new(300.0, 400.0), 15.0, 2.0));
+            let end = JointEnd::Segment {
+                rope: ids[5],
+                index: 19,
+            };
+            let local = Vec2::ZERO;
+            world
+                .add_joint(Joint::revolute(end, JointEnd::Body { body, local }))
+                .unwrap();
+            world
+        }
+
+        fn step_tangle() -> u64 {
+            let mut world = tangle();
+            for _ in 0..TANGLE_STEPS {
+                world.step(NOMINAL_DT);
+            }
+            world.checksum()
+        }
+
+        /// A pool of one thread ticks ropes serially, a bigger one in parallel.
+        #[test]
+        fn parallel_ticks_match_serial_ticks() {
+            let on = |threads| {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .unwrap();
+                pool.install(step_tangle)
+            };
+            assert_eq!(on(1), on(4));
+        }
+    }
+}